@@ -1,9 +1,32 @@
+use crate::favicon::{self, SiteIcon};
+use crate::fetch::HttpFetcher;
+use crate::icon;
+use crate::inject::{self, InjectFile, InjectStore};
+use crate::local_app;
+use crate::pake_capabilities::{self, CapabilitySource};
+use crate::pake_config::PakeConfig;
+use crate::preflight::{Preflight, PreflightReport, Severity};
 use crate::project::{Project, ProjectManager};
+use crate::project_env::{self, ProjectEnv, ProjectSecrets};
+use crate::doctor::{DoctorReport, ReportFormat};
+use crate::env_cache::EnvironmentCache;
 use crate::environment::{EnvironmentChecker, EnvironmentStatus};
+use crate::history::{now_millis, BuildHistory, BuildRecord, RecordedEnvVar};
+use crate::installer::{InstallOptions, InstallResult, Installer, PakeSource};
+use crate::linux_deps::{LinuxDependencyChecker, LinuxDependencyReport};
+use crate::search_path::{PathSettings, SearchPath};
+use crate::settings::SettingsManager;
+use crate::shell_env::{self, EffectiveEnvironment};
+use crate::shortcut::{self, ShortcutCheck};
+use crate::site_probe::{self, SiteProbe};
+use crate::snippets::{self, Snippet, SnippetInput, SnippetLibrary, SnippetUsage};
+use crate::toolchain::{self, ManagedToolchain, ManagedVersion, PAKE_BINARY, PAKE_PACKAGE};
+use crate::tools::{Platform, ToolRegistry, ToolSpec};
+use crate::validation::{self, FieldErrors, PlatformErrors, ProjectError};
 use serde_json::Value;
 use std::collections::HashMap;
-use tauri_plugin_shell::ShellExt;
 use std::path::PathBuf;
+use tauri_plugin_shell::ShellExt;
 use tauri::{Manager, Emitter};
 use std::process::Command as StdCommand;
 
@@ -20,9 +43,129 @@ pub async fn load_project(project_id: String) -> Result<Project, String> {
 }
 
 #[tauri::command]
-pub async fn save_project(project: Project) -> Result<(), String> {
+pub async fn save_project(project: Project) -> Result<Project, ProjectError> {
+    // 与前端无关的统一校验，命令行等其他入口保存的项目也必须合法
+    let config = validation::validate_value(&project.config, Platform::current())?;
     let manager = ProjectManager::new().map_err(|e| e.to_string())?;
-    manager.save_project(project).map_err(|e| e.to_string())
+
+    // 本地文件或目录和图标需要存在，保存时会复制和转换到项目中
+    let project_dir = manager.get_project_path(&project.id);
+    let mut errors = FieldErrors::default();
+    if let Err(e) = local_app::check(&config, &project_dir) {
+        errors.add("url", e);
+    }
+    if let Err(e) = icon::check(&config.icon, &project_dir) {
+        errors.add("icon", e);
+    }
+    if let Err(e) = inject::check(&config, &project_dir) {
+        errors.add("inject", e);
+    }
+    if !config.snippets.is_empty() {
+        let library = SnippetLibrary::new().and_then(|library| library.load()).map_err(|e| e.to_string())?;
+        if let Err(e) = snippets::check(&config, &library) {
+            errors.add("snippets", e);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.into());
+    }
+
+    // 返回保存后的项目，本地应用的 url 会改为项目内的路径
+    Ok(manager.save_project(project).map_err(|e| e.to_string())?)
+}
+
+/// 从网站的 <link> 图标、apple-touch-icon 和 web app manifest 中选择最清晰的图标下载到项目中，
+/// 返回的路径用作项目图标，保存项目时再转换成各平台需要的格式
+#[tauri::command]
+pub async fn fetch_site_icon(project_id: Option<String>, url: String) -> Result<SiteIcon, String> {
+    // 新项目还没有目录，先下载到临时目录，保存项目时会转换到项目中
+    let dir = match project_id {
+        Some(project_id) => {
+            let manager = ProjectManager::new().map_err(|e| e.to_string())?;
+            manager.get_project_path(&project_id).join(favicon::SITE_ICON_DIR)
+        }
+        None => std::env::temp_dir().join("pake-gui").join(favicon::SITE_ICON_DIR),
+    };
+    let fetcher = HttpFetcher::new()?;
+    favicon::download_best(&fetcher, url.trim(), &dir).await
+}
+
+/// 读取网站的 web app manifest 和页面信息，给出应用名称、图标、主题色和需要加入 safeDomain 的域名
+#[tauri::command]
+pub async fn probe_url(url: String) -> Result<SiteProbe, String> {
+    let fetcher = HttpFetcher::new()?;
+    site_probe::probe(&fetcher, url.trim(), Platform::current()).await
+}
+
+fn inject_store(project_id: &str) -> Result<InjectStore, String> {
+    let manager = ProjectManager::new().map_err(|e| e.to_string())?;
+    Ok(InjectStore::new(&manager.get_project_path(project_id)))
+}
+
+#[tauri::command]
+pub async fn list_inject_files(project_id: String) -> Result<Vec<InjectFile>, String> {
+    inject_store(&project_id)?.list().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn read_inject_file(project_id: String, name: String) -> Result<String, String> {
+    inject_store(&project_id)?.read(&name).map_err(|e| e.to_string())
+}
+
+/// 新建或修改项目 inject 目录中的 CSS/JS 文件，返回写入 inject 配置的相对路径
+#[tauri::command]
+pub async fn save_inject_file(project_id: String, name: String, content: String) -> Result<InjectFile, String> {
+    inject_store(&project_id)?.write(&name, &content).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_inject_file(project_id: String, name: String) -> Result<(), String> {
+    inject_store(&project_id)?.delete(&name).map_err(|e| e.to_string())
+}
+
+/// 把选择的 CSS/JS 文件复制到项目的 inject 目录
+#[tauri::command]
+pub async fn import_inject_file(project_id: String, path: String) -> Result<InjectFile, String> {
+    inject_store(&project_id)?
+        .import(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_snippets() -> Result<Vec<Snippet>, String> {
+    let library = SnippetLibrary::new().map_err(|e| e.to_string())?;
+    library.load().map_err(|e| e.to_string())
+}
+
+/// 新建或修改片段库中的片段，引用它的项目在下次构建时使用新版本
+#[tauri::command]
+pub async fn save_snippet(snippet: SnippetInput) -> Result<Snippet, String> {
+    let library = SnippetLibrary::new().map_err(|e| e.to_string())?;
+    library.upsert(snippet).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_snippet(id: String) -> Result<(), String> {
+    let library = SnippetLibrary::new().map_err(|e| e.to_string())?;
+    library.delete(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_snippet_usage(id: String) -> Result<Vec<SnippetUsage>, String> {
+    snippets::usage(&id).map_err(|e| e.to_string())
+}
+
+/// 解析快捷键，返回规范写法、当前平台上的显示形式和使用同一快捷键的其他项目
+#[tauri::command]
+pub async fn check_shortcut(project_id: Option<String>, shortcut: String) -> Result<ShortcutCheck, String> {
+    shortcut::check(&shortcut, project_id.as_deref().unwrap_or_default(), Platform::current()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_config(config: Value, platform: Option<Platform>) -> Result<PlatformErrors, String> {
+    let config = PakeConfig::from_value(&config)?;
+    let platforms = platform.map_or(Platform::ALL.to_vec(), |platform| vec![platform]);
+    Ok(validation::validate_platforms(&config, &platforms))
 }
 
 #[tauri::command]
@@ -51,19 +194,9 @@ pub async fn get_project_output_path(project_id: String) -> Result<Option<String
     let project = manager.load_project(&project_id).map_err(|e| e.to_string())?;
     
     // 尝试从项目配置中获取输出路径
-    if let Some(url) = project.config.get("url").and_then(|v| v.as_str()) {
-        let name = project.config.get("name").and_then(|v| v.as_str()).unwrap_or("app");
+    if let Ok(config) = PakeConfig::from_value(&project.config) {
         // 简单推断输出路径，实际应该根据构建过程确定
-        let output_name = if url.starts_with("http") {
-            name.to_string()
-        } else {
-            // 对于本地文件，使用文件名
-            PathBuf::from(url)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or(name)
-                .to_string()
-        };
+        let output_name = local_app::output_name(&config);
         
         // 构建可能的输出路径（在项目目录中）
         let project_dir = manager.get_project_path(&project_id);
@@ -84,188 +217,370 @@ pub async fn get_project_output_path(project_id: String) -> Result<Option<String
 }
 
 #[tauri::command]
-pub async fn check_environment() -> Result<HashMap<String, EnvironmentStatus>, String> {
-    let checker = EnvironmentChecker::new();
-    Ok(checker.check_all().await)
+pub async fn check_environment(
+    cache: tauri::State<'_, EnvironmentCache>,
+    force: Option<bool>,
+) -> Result<HashMap<String, EnvironmentStatus>, String> {
+    shell_env::load().await;
+    Ok(cache.get(force.unwrap_or(false)).await)
 }
 
 #[tauri::command]
-pub async fn install_tool(tool: String) -> Result<(), String> {
+pub async fn check_tool(cache: tauri::State<'_, EnvironmentCache>, tool: String) -> Result<EnvironmentStatus, String> {
+    shell_env::load().await;
     let checker = EnvironmentChecker::new();
-    checker.install_tool(&tool).await.map_err(|e| e.to_string())
+    let status = checker.check_tool(&tool).await.map_err(|e| e.to_string())?;
+    cache.update(&tool, status.clone()).await;
+    Ok(status)
 }
 
 #[tauri::command]
-pub async fn update_pake_config(_project_id: String, config: Value) -> Result<(), String> {
-    // 这个函数目前未被使用，保留以备将来使用
-    // 实现逻辑会涉及更新项目配置并同步到pake-cli
-    let _ = config; // 避免未使用变量警告
-    Ok(())
+pub async fn check_linux_dependencies() -> Result<LinuxDependencyReport, String> {
+    tokio::task::spawn_blocking(|| LinuxDependencyChecker::new().check())
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn build_pake_app(
-    app_handle: tauri::AppHandle, 
-    config: Value,
-    project_id: String
-) -> Result<(), String> {
-    // 获取主窗口用于发送事件
-    let window = app_handle.get_webview_window("main").ok_or("Failed to get main window")?;
+pub async fn install_tool(
+    app_handle: tauri::AppHandle,
+    cache: tauri::State<'_, EnvironmentCache>,
+    tool: String,
+    options: Option<InstallOptions>,
+) -> Result<InstallResult, String> {
+    shell_env::load().await;
+    let installer = Installer::new();
+    let options = options.unwrap_or_default();
     
-    let shell = app_handle.shell();
+    // 安装输出逐行推送给前端
+    let emitter = app_handle.clone();
+    let result = installer
+        .install(&tool, &options, move |output| {
+            let _ = emitter.emit("install-output", output);
+        })
+        .await
+        .map_err(|e| e.to_string())?;
     
-    let mut cmd_args = vec![];
+    cache.update(&tool, result.status.clone()).await;
+    let _ = app_handle.emit("install-finished", result.clone());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn list_pake_versions() -> Result<Vec<ManagedVersion>, String> {
+    let toolchain = ManagedToolchain::new().map_err(|e| e.to_string())?;
+    Ok(toolchain.list_versions(PAKE_PACKAGE, PAKE_BINARY))
+}
+
+#[tauri::command]
+pub async fn install_pake_version(
+    app_handle: tauri::AppHandle,
+    version: Option<String>,
+    source: Option<PakeSource>,
+) -> Result<ManagedVersion, String> {
+    shell_env::load().await;
+    let installer = Installer::new();
+    let source = source.unwrap_or_default();
     
-    // Build pake command from config
-    if let Some(url) = config.get("url").and_then(|v| v.as_str()) {
-        cmd_args.push(url.to_string());
-    } else {
-        return Err("URL is required".to_string());
+    let emitter = app_handle.clone();
+    installer
+        .install_pake_version(version.as_deref(), &source, move |output| {
+            let _ = emitter.emit("install-output", output);
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_pake_version(version: String) -> Result<(), String> {
+    let toolchain = ManagedToolchain::new().map_err(|e| e.to_string())?;
+    toolchain.remove_version(PAKE_PACKAGE, &version).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_tool_specs() -> Result<Vec<ToolSpec>, String> {
+    Ok(ToolRegistry::load().specs().to_vec())
+}
+
+#[tauri::command]
+pub async fn save_custom_tool(tool: ToolSpec) -> Result<(), String> {
+    if tool.id.trim().is_empty() || tool.name.trim().is_empty() {
+        return Err("Tool id and name are required".to_string());
     }
-    
-    if let Some(name) = config.get("name").and_then(|v| v.as_str()) {
-        if !name.is_empty() {
-            cmd_args.push("--name".to_string());
-            cmd_args.push(name.to_string());
-        }
+    if tool.binaries.is_empty() && tool.paths.is_empty() {
+        return Err("A tool needs at least one binary name or path to check".to_string());
     }
-    
-    if let Some(icon) = config.get("icon").and_then(|v| v.as_str()) {
-        if !icon.is_empty() {
-            cmd_args.push("--icon".to_string());
-            cmd_args.push(icon.to_string());
-        }
+    for pattern in tool.version_regex.iter().chain(tool.requires.iter()) {
+        regex::Regex::new(pattern).map_err(|e| format!("Invalid regex {}: {}", pattern, e))?;
     }
     
-    if let Some(width) = config.get("width").and_then(|v| v.as_u64()) {
-        if width != 1200 {
-            cmd_args.push("--width".to_string());
-            cmd_args.push(width.to_string());
-        }
+    let manager = SettingsManager::new().map_err(|e| e.to_string())?;
+    let mut settings = manager.load().map_err(|e| e.to_string())?;
+    match settings.custom_tools.iter_mut().find(|t| t.id == tool.id) {
+        Some(existing) => *existing = tool,
+        None => settings.custom_tools.push(tool),
     }
-    
-    if let Some(height) = config.get("height").and_then(|v| v.as_u64()) {
-        if height != 780 {
-            cmd_args.push("--height".to_string());
-            cmd_args.push(height.to_string());
+    manager.save(&settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_custom_tool(tool_id: String) -> Result<(), String> {
+    let manager = SettingsManager::new().map_err(|e| e.to_string())?;
+    let mut settings = manager.load().map_err(|e| e.to_string())?;
+    settings.custom_tools.retain(|t| t.id != tool_id);
+    manager.save(&settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_path_settings() -> Result<PathSettings, String> {
+    Ok(SearchPath::settings())
+}
+
+#[tauri::command]
+pub async fn save_path_settings(binary_paths: HashMap<String, String>, extra_path: Vec<String>) -> Result<(), String> {
+    let binary_paths: HashMap<String, String> = binary_paths
+        .into_iter()
+        .map(|(binary, path)| (binary.trim().to_string(), path.trim().to_string()))
+        .filter(|(binary, path)| !binary.is_empty() && !path.is_empty())
+        .collect();
+    for (binary, path) in &binary_paths {
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("Path for {} does not exist or is not a file: {}", binary, path));
         }
     }
+    let extra_path: Vec<String> = extra_path
+        .into_iter()
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .collect();
     
-    if config.get("useLocalFile").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--use-local-file".to_string());
-    }
+    let manager = SettingsManager::new().map_err(|e| e.to_string())?;
+    let mut settings = manager.load().map_err(|e| e.to_string())?;
+    settings.binary_paths = binary_paths;
+    settings.extra_path = extra_path;
+    manager.save(&settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_effective_environment() -> Result<EffectiveEnvironment, String> {
+    shell_env::load().await;
+    Ok(shell_env::effective(&SearchPath::load().path_env()))
+}
+
+#[tauri::command]
+pub async fn generate_doctor_report(
+    format: Option<ReportFormat>,
+    save_path: Option<String>,
+) -> Result<String, String> {
+    let projects_dir = ProjectManager::new().map_err(|e| e.to_string())?.projects_dir().to_path_buf();
+    let report = DoctorReport::collect(&projects_dir).await;
+    let content = report
+        .render(format.unwrap_or(ReportFormat::Markdown))
+        .map_err(|e| e.to_string())?;
     
-    if config.get("fullscreen").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--fullscreen".to_string());
+    // 指定了路径时同时保存到文件
+    if let Some(path) = save_path {
+        std::fs::write(&path, &content).map_err(|e| format!("Failed to save report to {}: {}", path, e))?;
     }
+    Ok(content)
+}
+
+#[tauri::command]
+pub async fn update_pake_config(_project_id: String, config: Value) -> Result<(), String> {
+    // 这个函数目前未被使用，保留以备将来使用
+    // 实现逻辑会涉及更新项目配置并同步到pake-cli
+    let _ = config; // 避免未使用变量警告
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn validate_project(
+    cache: tauri::State<'_, EnvironmentCache>,
+    project_id: String,
+    config: Value,
+) -> Result<PreflightReport, String> {
+    let pake_config = PakeConfig::from_value(&config)?;
+    let project_env = ProjectEnv::from_config(&config);
+    let manager = ProjectManager::new().map_err(|e| e.to_string())?;
+    let project_dir = manager.get_project_path(&project_id);
     
-    if config.get("hideTitleBar").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--hide-title-bar".to_string());
-    }
+    shell_env::load().await;
+    let environment = cache.get(false).await;
+    let capabilities = pake_capabilities::detect(&pake_config, &SearchPath::load()).await;
+    Ok(Preflight::new(&pake_config, &project_env, &project_dir, &environment)
+        .with_capabilities(capabilities.as_ref())
+        .run())
+}
+
+#[tauri::command]
+pub async fn build_pake_app(
+    app_handle: tauri::AppHandle, 
+    cache: tauri::State<'_, EnvironmentCache>,
+    config: Value,
+    project_id: String
+) -> Result<(), String> {
+    // 获取主窗口用于发送事件
+    let window = app_handle.get_webview_window("main").ok_or("Failed to get main window")?;
     
-    if config.get("multiArch").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--multi-arch".to_string());
-    }
+    let shell = app_handle.shell();
     
-    if config.get("debug").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--debug".to_string());
-    }
+    let mut pake_config = PakeConfig::from_value(&config)?;
     
-    if let Some(shortcut) = config.get("activationShortcut").and_then(|v| v.as_str()) {
-        if !shortcut.is_empty() {
-            cmd_args.push("--activation-shortcut".to_string());
-            cmd_args.push(shortcut.to_string());
-        }
-    }
+    // 获取项目目录作为工作目录
+    let manager = ProjectManager::new().map_err(|e| e.to_string())?;
+    let project_dir = manager.get_project_path(&project_id);
     
-    if config.get("alwaysOnTop").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--always-on-top".to_string());
+    // 确保项目目录存在
+    if !project_dir.exists() {
+        std::fs::create_dir_all(&project_dir).map_err(|e| format!("Failed to create project directory: {}", e))?;
     }
     
-    if let Some(targets) = config.get("targets").and_then(|v| v.as_str()) {
-        if targets != "all" {
-            cmd_args.push("--targets".to_string());
-            cmd_args.push(targets.to_string());
-        }
-    }
+    shell_env::load().await;
+    let project_env = ProjectEnv::from_config(&config);
     
-    if let Some(user_agent) = config.get("userAgent").and_then(|v| v.as_str()) {
-        if !user_agent.is_empty() {
-            cmd_args.push("--user-agent".to_string());
-            cmd_args.push(user_agent.to_string());
-        }
+    // 构建前检查配置和工具链，有错误时不启动 pake
+    let environment = cache.get(false).await;
+    // 按所装 pake-cli 版本的选项表检查参数，不支持的选项在启动 pake 之前报告
+    let capabilities = pake_capabilities::detect(&pake_config, &SearchPath::load()).await;
+    if let Some(capabilities) = &capabilities {
+        let source = match capabilities.source {
+            CapabilitySource::Help => "pake --help",
+            CapabilitySource::Fixture => "built-in option table",
+        };
+        let _ = window.emit("build-output", format!("pake-cli {}: options from {}", capabilities.version, source));
     }
-    
-    if config.get("showSystemTray").and_then(|v| v.as_bool()).unwrap_or(false) {
-        cmd_args.push("--show-system-tray".to_string());
+    let report = Preflight::new(&pake_config, &project_env, &project_dir, &environment)
+        .with_capabilities(capabilities.as_ref())
+        .run();
+    for issue in &report.issues {
+        let severity = match issue.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let _ = window.emit("build-output", format!("preflight {}: {}", severity, issue.message));
     }
-    
-    if let Some(tray_icon) = config.get("systemTrayIcon").and_then(|v| v.as_str()) {
-        if !tray_icon.is_empty() {
-            cmd_args.push("--system-tray-icon".to_string());
-            cmd_args.push(tray_icon.to_string());
-        }
+    if report.has_errors() {
+        let errors: Vec<&str> = report.errors().map(|issue| issue.message.as_str()).collect();
+        return Err(format!("Preflight check failed: {}", errors.join("; ")));
     }
     
-    if let Some(inject_array) = config.get("inject").and_then(|v| v.as_array()) {
-        for inject_file in inject_array {
-            if let Some(file) = inject_file.as_str() {
-                cmd_args.push("--inject".to_string());
-                cmd_args.push(file.to_string());
-            }
-        }
-    }
+    local_app::resolve_for_build(&mut pake_config, &project_dir);
     
-    if let Some(safe_domains) = config.get("safeDomain").and_then(|v| v.as_array()) {
-        for domain in safe_domains {
-            if let Some(domain_str) = domain.as_str() {
-                cmd_args.push("--safe-domain".to_string());
-                cmd_args.push(domain_str.to_string());
-            }
-        }
+    // 引用的片段按片段库中的最新版本写入项目，和其他注入文件一起传给 pake
+    let applied = snippets::materialize(&pake_config, &project_dir).map_err(|e| e.to_string())?;
+    for snippet in applied {
+        let _ = window.emit("build-output", format!("snippet: {} (v{})", snippet.id, snippet.version));
+        pake_config.inject.push(snippet.path.to_string_lossy().to_string());
     }
     
-    // 获取项目目录作为工作目录
-    let manager = ProjectManager::new().map_err(|e| e.to_string())?;
-    let project_dir = manager.get_project_path(&project_id);
+    // 项目固定了 pake-cli 版本时使用缓存中的对应版本
+    let search_path = SearchPath::load();
+    let pake = ManagedToolchain::new()
+        .and_then(|toolchain| toolchain.resolve_pake(pake_config.pinned_version(), &search_path))
+        .map_err(|e| e.to_string())?;
+    // 项目自己的环境变量和代理，密钥从项目目录下的 .secrets.json 中读取
+    let secrets = ProjectSecrets::load(&project_dir).map_err(|e| e.to_string())?;
+    let project_vars = project_env.resolve(&secrets)?;
+    pake_config.proxy_url = project_env.resolve_proxy_url(&secrets)?.unwrap_or_default();
     
-    // 确保项目目录存在
-    if !project_dir.exists() {
-        std::fs::create_dir_all(&project_dir).map_err(|e| format!("Failed to create project directory: {}", e))?;
-    }
+    let cmd_args = pake_config.to_args();
+    
+    let history = BuildHistory::new(&project_dir);
+    let started_at = now_millis();
+    // 构建历史中的命令行不保存代理密码
+    let redacted_args: Vec<String> = cmd_args.iter().map(|arg| project_env::redact_url(arg)).collect();
+    let command_line = format!("{} {}", pake.to_string_lossy(), redacted_args.join(" "));
     
     // Execute pake command with real-time output in the project directory
-    let (mut rx, _child) = shell
-        .command("pake")
-        .args(&cmd_args)
+    // Windows 上的 pake.cmd 经由 cmd.exe 执行会解释参数中的 & ( % 等字符，改用 node 直接运行 pake 的入口脚本
+    let (program, mut spawn_args) = match toolchain::cmd_shim_script(&pake) {
+        Some(script) => (
+            search_path.which("node").unwrap_or_else(|| PathBuf::from("node")),
+            vec![script.to_string_lossy().to_string()],
+        ),
+        None => (pake.clone(), Vec::new()),
+    };
+    spawn_args.extend(cmd_args.iter().cloned());
+    let spawned = shell
+        .command(program.to_string_lossy().to_string())
+        .args(&spawn_args)
+        .envs(shell_env::imported_vars())
+        .envs(project_vars)
+        .env("PATH", search_path.path_env())
         .current_dir(project_dir) // 设置工作目录为项目目录
         .spawn()
-        .map_err(|e| format!("Failed to start pake command: {}", e))?;
-
-    // Listen to the command stream and send output to the frontend
-    while let Some(event) = rx.recv().await {
-        match event {
-            tauri_plugin_shell::process::CommandEvent::Stdout(line) => {
-                let _ = window.emit("build-output", format!("stdout: {}", String::from_utf8_lossy(&line)));
-            }
-            tauri_plugin_shell::process::CommandEvent::Stderr(line) => {
-                let _ = window.emit("build-output", format!("stderr: {}", String::from_utf8_lossy(&line)));
-            }
-            tauri_plugin_shell::process::CommandEvent::Error(error) => {
-                let _ = window.emit("build-output", format!("error: {}", error));
-                return Err(format!("Command error: {}", error));
-            }
-            tauri_plugin_shell::process::CommandEvent::Terminated(payload) => {
-                if !payload.code.map_or(true, |code| code == 0) {
-                    return Err("Command terminated with non-zero exit code".to_string());
+        .map_err(|e| format!("Failed to start pake command: {}", e));
+    
+    let mut exit_code = None;
+    let result = match spawned {
+        Ok((mut rx, _child)) => {
+            let mut result = Err("pake exited without reporting a status".to_string());
+            // Listen to the command stream and send output to the frontend
+            while let Some(event) = rx.recv().await {
+                match event {
+                    tauri_plugin_shell::process::CommandEvent::Stdout(line) => {
+                        let _ = window.emit("build-output", format!("stdout: {}", String::from_utf8_lossy(&line)));
+                    }
+                    tauri_plugin_shell::process::CommandEvent::Stderr(line) => {
+                        let _ = window.emit("build-output", format!("stderr: {}", String::from_utf8_lossy(&line)));
+                    }
+                    tauri_plugin_shell::process::CommandEvent::Error(error) => {
+                        let _ = window.emit("build-output", format!("error: {}", error));
+                        result = Err(format!("Command error: {}", error));
+                        break;
+                    }
+                    tauri_plugin_shell::process::CommandEvent::Terminated(payload) => {
+                        exit_code = payload.code;
+                        result = if payload.code.map_or(true, |code| code == 0) {
+                            Ok(())
+                        } else {
+                            Err("Command terminated with non-zero exit code".to_string())
+                        };
+                        break;
+                    }
+                    _ => {}
                 }
-                break;
             }
-            _ => {}
+            result
         }
+        Err(e) => Err(e),
+    };
+    
+    // 构建历史中的环境变量已脱敏
+    let record = BuildRecord {
+        started_at,
+        finished_at: now_millis(),
+        success: result.is_ok(),
+        exit_code,
+        command: command_line,
+        env: project_env
+            .redacted()
+            .into_iter()
+            .map(|(name, value)| RecordedEnvVar { name, value })
+            .collect(),
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = history.append(record) {
+        let _ = window.emit("build-output", format!("Failed to save build history: {}", e));
     }
     
-    Ok(())
+    result
+}
+
+#[tauri::command]
+pub async fn get_build_history(project_id: String) -> Result<Vec<BuildRecord>, String> {
+    let manager = ProjectManager::new().map_err(|e| e.to_string())?;
+    BuildHistory::new(&manager.get_project_path(&project_id))
+        .load()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_build_history(project_id: String) -> Result<(), String> {
+    let manager = ProjectManager::new().map_err(|e| e.to_string())?;
+    BuildHistory::new(&manager.get_project_path(&project_id))
+        .clear()
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -0,0 +1,240 @@
+use crate::environment::{EnvironmentChecker, EnvironmentStatus, Status};
+use crate::linux_deps::{LinuxDependencyChecker, LinuxDependencyReport};
+use crate::search_path::SearchPath;
+use crate::settings::SettingsManager;
+use crate::shell_env;
+use crate::toolchain::{ManagedToolchain, PAKE_BINARY, PAKE_PACKAGE};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+    pub os: String,
+    pub os_version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub arch: String,
+    pub app_version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpace {
+    pub path: String,
+    pub mount_point: String,
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// pake-cli 相关的配置：实际使用的可执行文件、缓存的版本和安装源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PakeInfo {
+    pub binary: Option<String>,
+    pub path_override: Option<String>,
+    pub cached_versions: Vec<String>,
+    pub registry: Option<String>,
+}
+
+/// 排查构建问题时需要的全部环境信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    pub generated_at: u64,
+    pub system: SystemInfo,
+    pub login_shell: Option<String>,
+    pub login_shell_error: Option<String>,
+    pub path: Vec<String>,
+    pub tools: BTreeMap<String, EnvironmentStatus>,
+    pub linux_dependencies: Option<LinuxDependencyReport>,
+    pub disk: Option<DiskSpace>,
+    pub pake: PakeInfo,
+}
+
+impl DoctorReport {
+    pub async fn collect(projects_root: &Path) -> Self {
+        let login = shell_env::load().await;
+        let search_path = SearchPath::load();
+
+        let tools: BTreeMap<String, EnvironmentStatus> =
+            EnvironmentChecker::new().check_all().await.into_iter().collect();
+
+        let linux_dependencies = if cfg!(target_os = "linux") {
+            tokio::task::spawn_blocking(|| LinuxDependencyChecker::new().check()).await.ok()
+        } else {
+            None
+        };
+
+        let settings = SettingsManager::new()
+            .and_then(|manager| manager.load())
+            .unwrap_or_default();
+        let toolchain = ManagedToolchain::new().ok();
+        let pake = PakeInfo {
+            binary: toolchain
+                .as_ref()
+                .and_then(|toolchain| toolchain.resolve_pake(None, &search_path).ok())
+                .map(|path| path.to_string_lossy().to_string()),
+            path_override: search_path.override_for(PAKE_BINARY).map(str::to_string),
+            cached_versions: toolchain
+                .map(|toolchain| {
+                    toolchain
+                        .list_versions(PAKE_PACKAGE, PAKE_BINARY)
+                        .into_iter()
+                        .map(|v| v.version)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            registry: settings.pake_registry,
+        };
+
+        Self {
+            generated_at: crate::history::now_millis(),
+            system: SystemInfo {
+                os: std::env::consts::OS.to_string(),
+                os_version: sysinfo::System::long_os_version(),
+                kernel_version: sysinfo::System::kernel_version(),
+                arch: std::env::consts::ARCH.to_string(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            login_shell: login.shell.clone(),
+            login_shell_error: login.error.clone(),
+            path: std::env::split_paths(&search_path.path_env())
+                .map(|dir| dir.to_string_lossy().to_string())
+                .collect(),
+            tools,
+            linux_dependencies,
+            disk: disk_space(projects_root),
+            pake,
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String, Box<dyn std::error::Error>> {
+        match format {
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ReportFormat::Markdown => Ok(self.to_markdown()?),
+        }
+    }
+
+    fn to_markdown(&self) -> Result<String, std::fmt::Error> {
+        let mut out = String::new();
+        let none = || "-".to_string();
+
+        writeln!(out, "# Pake GUI Doctor Report")?;
+        writeln!(out)?;
+        writeln!(out, "## System")?;
+        writeln!(out)?;
+        writeln!(out, "- OS: {} ({})", self.system.os_version.clone().unwrap_or_else(|| self.system.os.clone()), self.system.arch)?;
+        writeln!(out, "- Kernel: {}", self.system.kernel_version.clone().unwrap_or_else(none))?;
+        writeln!(out, "- Pake GUI: {}", self.system.app_version)?;
+        match (&self.login_shell, &self.login_shell_error) {
+            (Some(shell), Some(error)) => writeln!(out, "- Login shell: {} (import failed: {})", shell, error)?,
+            (Some(shell), None) => writeln!(out, "- Login shell: {}", shell)?,
+            _ => writeln!(out, "- Login shell: -")?,
+        }
+        if let Some(disk) = &self.disk {
+            writeln!(
+                out,
+                "- Disk space ({}): {} free of {}",
+                disk.path,
+                format_bytes(disk.available_bytes),
+                format_bytes(disk.total_bytes)
+            )?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Tools")?;
+        writeln!(out)?;
+        writeln!(out, "| Tool | Status | Version | Path | Diagnosis |")?;
+        writeln!(out, "| --- | --- | --- | --- | --- |")?;
+        for (id, status) in &self.tools {
+            let state = match status.status {
+                Status::Ok => "ok",
+                Status::Warning => "warning",
+                Status::Error => "error",
+            };
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                id,
+                state,
+                status.version.clone().unwrap_or_else(none),
+                status.path.clone().unwrap_or_else(none),
+                status.diagnosis.clone().unwrap_or_else(none).replace('|', "\\|"),
+            )?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Pake CLI")?;
+        writeln!(out)?;
+        writeln!(out, "- Binary: {}", self.pake.binary.clone().unwrap_or_else(|| "not found".to_string()))?;
+        writeln!(out, "- Path override: {}", self.pake.path_override.clone().unwrap_or_else(none))?;
+        writeln!(
+            out,
+            "- Cached versions: {}",
+            if self.pake.cached_versions.is_empty() { "-".to_string() } else { self.pake.cached_versions.join(", ") }
+        )?;
+        writeln!(out, "- Registry: {}", self.pake.registry.clone().unwrap_or_else(none))?;
+        writeln!(out)?;
+
+        if let Some(report) = &self.linux_dependencies {
+            writeln!(out, "## Linux Dependencies ({:?})", report.distro)?;
+            writeln!(out)?;
+            for dependency in &report.dependencies {
+                writeln!(
+                    out,
+                    "- [{}] {} {}",
+                    if dependency.installed { "x" } else { " " },
+                    dependency.name,
+                    dependency.version.clone().unwrap_or_default()
+                )?;
+            }
+            if let Some(command) = &report.install_command {
+                writeln!(out)?;
+                writeln!(out, "Install missing packages: `{}`", command)?;
+            }
+            writeln!(out)?;
+        }
+
+        writeln!(out, "## PATH")?;
+        writeln!(out)?;
+        writeln!(out, "```")?;
+        for dir in &self.path {
+            writeln!(out, "{}", dir)?;
+        }
+        writeln!(out, "```")?;
+
+        Ok(out)
+    }
+}
+
+/// 项目根目录所在磁盘的剩余空间
+fn disk_space(path: &Path) -> Option<DiskSpace> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    // 挂载点可能嵌套，取最长的匹配
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())?;
+
+    Some(DiskSpace {
+        path: path.to_string_lossy().to_string(),
+        mount_point: disk.mount_point().to_string_lossy().to_string(),
+        available_bytes: disk.available_space(),
+        total_bytes: disk.total_space(),
+    })
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    format!("{:.1} GB", bytes as f64 / GB)
+}
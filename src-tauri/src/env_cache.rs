@@ -0,0 +1,113 @@
+use crate::environment::{EnvironmentChecker, EnvironmentStatus};
+use crate::search_path::SearchPath;
+use crate::settings::SettingsManager;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+// 后台检查 PATH 和可执行文件是否变化的间隔，只做 stat，开销很小
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 影响检查结果的文件及其修改时间：PATH 中的目录（安装新工具会改变目录的 mtime）、
+/// 已找到的可执行文件和设置文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint(Vec<(PathBuf, Option<SystemTime>)>);
+
+impl Fingerprint {
+    fn current(results: &HashMap<String, EnvironmentStatus>) -> Self {
+        let mut paths: Vec<PathBuf> = std::env::split_paths(&SearchPath::load().path_env()).collect();
+
+        let mut binaries: Vec<PathBuf> = results
+            .values()
+            .filter_map(|status| status.path.as_ref())
+            .map(PathBuf::from)
+            .collect();
+        binaries.sort();
+        paths.extend(binaries);
+
+        if let Ok(manager) = SettingsManager::new() {
+            paths.push(manager.path().to_path_buf());
+        }
+
+        Self(
+            paths
+                .into_iter()
+                .map(|path| {
+                    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                    (path, modified)
+                })
+                .collect(),
+        )
+    }
+}
+
+struct Snapshot {
+    results: HashMap<String, EnvironmentStatus>,
+    fingerprint: Fingerprint,
+}
+
+/// 保存在 Tauri state 中的环境检查结果，PATH 或可执行文件变化后失效
+#[derive(Default)]
+pub struct EnvironmentCache {
+    snapshot: Mutex<Option<Snapshot>>,
+}
+
+impl EnvironmentCache {
+    /// 返回缓存的结果；没有缓存、强制刷新或环境已变化时重新检查
+    pub async fn get(&self, force: bool) -> HashMap<String, EnvironmentStatus> {
+        let mut snapshot = self.snapshot.lock().await;
+
+        if let Some(cached) = snapshot.as_ref() {
+            if !force && Fingerprint::current(&cached.results) == cached.fingerprint {
+                return cached.results.clone();
+            }
+        }
+
+        let results = EnvironmentChecker::new().check_all().await;
+        *snapshot = Some(Snapshot {
+            fingerprint: Fingerprint::current(&results),
+            results: results.clone(),
+        });
+        results
+    }
+
+    /// 单独重新检查或安装某个工具后更新缓存中的对应条目
+    pub async fn update(&self, tool: &str, status: EnvironmentStatus) {
+        let mut snapshot = self.snapshot.lock().await;
+        if let Some(cached) = snapshot.as_mut() {
+            cached.results.insert(tool.to_string(), status);
+            cached.fingerprint = Fingerprint::current(&cached.results);
+        }
+    }
+
+    /// 已有缓存且环境发生变化时重新检查，返回新的结果
+    async fn refresh_if_changed(&self) -> Option<HashMap<String, EnvironmentStatus>> {
+        let changed = {
+            let snapshot = self.snapshot.lock().await;
+            let cached = snapshot.as_ref()?;
+            Fingerprint::current(&cached.results) != cached.fingerprint
+        };
+        if !changed {
+            return None;
+        }
+        Some(self.get(true).await)
+    }
+}
+
+/// 在后台监视环境变化，例如用户在终端中安装了工具，变化后推送 environment-changed 事件
+pub fn watch(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+
+            let Some(cache) = app_handle.try_state::<EnvironmentCache>() else {
+                continue;
+            };
+            if let Some(results) = cache.refresh_if_changed().await {
+                let _ = app_handle.emit("environment-changed", results);
+            }
+        }
+    });
+}
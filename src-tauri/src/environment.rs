@@ -1,247 +1,421 @@
+use crate::history::now_millis;
+use crate::linux_deps::LinuxDependencyChecker;
+use crate::search_path::SearchPath;
+use crate::shell_env;
+use crate::toolchain::ManagedToolchain;
+use crate::tools::{compare_versions, extract_version, InstallStrategy, ToolRegistry, ToolSpec};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::process::Command;
-use which::which;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command as AsyncCommand;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warning,
+    Error,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvironmentStatus {
-    pub status: String, // "ok", "error", "warning", "checking"
+    pub status: Status,
     pub version: Option<String>,
     pub path: Option<String>,
+    // 人类可读的诊断信息，说明为什么是当前状态
+    pub diagnosis: Option<String>,
+    // 实际探测时执行的命令，例如 "pake --version"
+    pub command: Option<String>,
+    pub stderr: Option<String>,
+    #[serde(rename = "suggestedFix")]
+    pub suggested_fix: Option<String>,
+    // 同一工具可能同时存在全局安装和 GUI 管理的安装
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub installations: Vec<ToolInstallation>,
+    // 检查时间（Unix 毫秒），用于显示缓存结果的新旧
+    #[serde(rename = "checkedAt", default, skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<u64>,
 }
 
-pub struct EnvironmentChecker;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallationKind {
+    Global,
+    Managed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInstallation {
+    pub kind: InstallationKind,
+    pub status: Status,
+    pub version: Option<String>,
+    pub path: Option<String>,
+}
+
+// 单个探测命令的超时时间，避免卡死的 `pake --version` 拖住整个检查
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// stderr 可能非常长（例如 node 的堆栈），只保留末尾部分
+const MAX_STDERR_LEN: usize = 2000;
+
+impl EnvironmentStatus {
+    pub fn ok(version: Option<String>, path: Option<String>) -> Self {
+        Self::new(Status::Ok, version, path)
+    }
+
+    pub fn warning(diagnosis: impl Into<String>) -> Self {
+        Self::new(Status::Warning, None, None).with_diagnosis(diagnosis)
+    }
+
+    pub fn error(diagnosis: impl Into<String>) -> Self {
+        Self::new(Status::Error, None, None).with_diagnosis(diagnosis)
+    }
+
+    fn new(status: Status, version: Option<String>, path: Option<String>) -> Self {
+        Self {
+            status,
+            version,
+            path,
+            diagnosis: None,
+            command: None,
+            stderr: None,
+            suggested_fix: None,
+            installations: Vec::new(),
+            checked_at: None,
+        }
+    }
+
+    pub fn with_diagnosis(mut self, diagnosis: impl Into<String>) -> Self {
+        self.diagnosis = Some(diagnosis.into());
+        self
+    }
+
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    pub fn with_stderr(mut self, stderr: &[u8]) -> Self {
+        let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+        if !stderr.is_empty() {
+            // 保留最后 MAX_STDERR_LEN 个字符，截断时在开头加省略号
+            let len = stderr.chars().count();
+            self.stderr = Some(match stderr.char_indices().nth(len.saturating_sub(MAX_STDERR_LEN)) {
+                Some((start, _)) if start > 0 => format!("…{}", &stderr[start..]),
+                _ => stderr,
+            });
+        }
+        self
+    }
+
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.suggested_fix = Some(fix.into());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_checked_at(mut self, checked_at: u64) -> Self {
+        self.checked_at = Some(checked_at);
+        self
+    }
+}
+
+impl ToolInstallation {
+    fn from_status(kind: InstallationKind, status: &EnvironmentStatus) -> Self {
+        Self {
+            kind,
+            status: status.status,
+            version: status.version.clone(),
+            path: status.path.clone(),
+        }
+    }
+}
+
+pub struct EnvironmentChecker {
+    registry: ToolRegistry,
+    search_path: SearchPath,
+}
 
 impl EnvironmentChecker {
     pub fn new() -> Self {
-        Self
+        Self {
+            registry: ToolRegistry::load(),
+            search_path: SearchPath::load(),
+        }
     }
     
     pub async fn check_all(&self) -> HashMap<String, EnvironmentStatus> {
-        let mut results = HashMap::new();
+        // 所有探测并发执行，总耗时取决于最慢的那一个
+        let specs = self.registry.specs();
+        let (statuses, linux_deps) = futures::join!(
+            futures::future::join_all(specs.iter().map(|spec| self.check_spec(spec))),
+            self.check_platform_deps(),
+        );
         
-        results.insert("nodejs".to_string(), self.check_nodejs().await);
-        results.insert("bunjs".to_string(), self.check_bunjs().await);
-        results.insert("rust".to_string(), self.check_rust().await);
-        results.insert("visualStudio".to_string(), self.check_visual_studio().await);
-        results.insert("pake".to_string(), self.check_pake().await);
+        let checked_at = now_millis();
+        let mut results: HashMap<String, EnvironmentStatus> = specs
+            .iter()
+            .map(|spec| spec.id.clone())
+            .zip(statuses.into_iter().map(|status| status.with_checked_at(checked_at)))
+            .collect();
+        if let Some(linux_deps) = linux_deps {
+            results.insert("linuxDeps".to_string(), linux_deps.with_checked_at(checked_at));
+        }
         
         results
     }
     
-    async fn check_nodejs(&self) -> EnvironmentStatus {
-        match which("node") {
-            Ok(path) => {
-                if let Ok(output) = Command::new("node").args(&["--version"]).output() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    EnvironmentStatus {
-                        status: "ok".to_string(),
-                        version: Some(version),
-                        path: Some(path.to_string_lossy().to_string()),
-                    }
-                } else {
-                    EnvironmentStatus {
-                        status: "error".to_string(),
-                        version: None,
-                        path: None,
-                    }
-                }
-            }
-            Err(_) => EnvironmentStatus {
-                status: "error".to_string(),
-                version: None,
-                path: None,
-            },
+    /// 重新检查单个工具
+    pub async fn check_tool(&self, tool: &str) -> Result<EnvironmentStatus, Box<dyn std::error::Error>> {
+        #[cfg(target_os = "linux")]
+        if tool == "linuxDeps" {
+            return Ok(self.check_linux_deps().await.with_checked_at(now_millis()));
         }
+        
+        let spec = self.registry.get(tool).ok_or_else(|| format!("Unknown tool: {}", tool))?;
+        Ok(self.check_spec(spec).await.with_checked_at(now_millis()))
     }
     
-    async fn check_bunjs(&self) -> EnvironmentStatus {
-        match which("bun") {
-            Ok(path) => {
-                if let Ok(output) = Command::new("bun").args(&["--version"]).output() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    EnvironmentStatus {
-                        status: "ok".to_string(),
-                        version: Some(version),
-                        path: Some(path.to_string_lossy().to_string()),
-                    }
+    pub async fn check_spec(&self, spec: &ToolSpec) -> EnvironmentStatus {
+        if !spec.supports_current_platform() {
+            return EnvironmentStatus::ok(Some("Not required on this platform".to_string()), None);
+        }
+        
+        if spec.binaries.is_empty() {
+            return Self::check_paths(spec);
+        }
+        
+        let global = self.probe(spec).await;
+        
+        // 通过包管理器安装的工具还可能装在 GUI 管理的目录中
+        let managed_path = match &spec.install {
+            InstallStrategy::GlobalPackage { package, .. } => ManagedToolchain::new()
+                .ok()
+                .and_then(|toolchain| toolchain.find_binary(package, &spec.binaries[0])),
+            _ => None,
+        };
+        let managed = match managed_path {
+            Some(path) => Some(self.run_probe(spec, &spec.binaries[0], &path.to_string_lossy()).await),
+            None => None,
+        };
+        
+        let status = match managed {
+            Some(managed) => {
+                let installations = vec![
+                    ToolInstallation::from_status(InstallationKind::Global, &global),
+                    ToolInstallation::from_status(InstallationKind::Managed, &managed),
+                ];
+                // 全局安装可用时优先报告全局安装
+                let primary = if global.status == Status::Ok || managed.status != Status::Ok {
+                    global
                 } else {
-                    EnvironmentStatus {
-                        status: "error".to_string(),
-                        version: None,
-                        path: None,
-                    }
+                    managed
+                };
+                EnvironmentStatus { installations, ..primary }
+            }
+            None => global,
+        };
+        if status.status != Status::Ok {
+            return status;
+        }
+        
+        // 版本过低时给出警告而不是错误，旧版本通常仍然可以尝试构建
+        if let (Some(min), Some(version)) = (&spec.min_version, status.version.clone()) {
+            if compare_versions(&version, min) == Some(Ordering::Less) {
+                return EnvironmentStatus {
+                    status: Status::Warning,
+                    ..status
                 }
+                .with_diagnosis(format!("{} {} found, but {} or newer is required", spec.name, version, min))
+                .with_fix(spec.install_hint());
             }
-            Err(_) => EnvironmentStatus {
-                status: "error".to_string(),
-                version: None,
-                path: None,
-            },
         }
+        
+        status
     }
     
-    async fn check_rust(&self) -> EnvironmentStatus {
-        match which("rustc") {
-            Ok(path) => {
-                if let Ok(output) = Command::new("rustc").args(&["--version"]).output() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    EnvironmentStatus {
-                        status: "ok".to_string(),
-                        version: Some(version),
-                        path: Some(path.to_string_lossy().to_string()),
-                    }
-                } else {
-                    EnvironmentStatus {
-                        status: "error".to_string(),
-                        version: None,
-                        path: None,
-                    }
-                }
+    fn check_paths(spec: &ToolSpec) -> EnvironmentStatus {
+        for path in &spec.paths {
+            if Path::new(path).exists() {
+                return EnvironmentStatus::ok(Some("Found".to_string()), Some(path.clone()));
             }
-            Err(_) => EnvironmentStatus {
-                status: "error".to_string(),
-                version: None,
-                path: None,
-            },
         }
+        
+        EnvironmentStatus::error(format!(
+            "{} was not found in any of: {}",
+            spec.name,
+            spec.paths.join(", ")
+        ))
+        .with_fix(spec.install_hint())
     }
     
-    async fn check_visual_studio(&self) -> EnvironmentStatus {
-        // Check for Visual Studio Build Tools on Windows
-        #[cfg(target_os = "windows")]
+    async fn check_platform_deps(&self) -> Option<EnvironmentStatus> {
+        #[cfg(target_os = "linux")]
         {
-            use std::path::Path;
-            
-            let vs_paths = vec![
-                "C:\\Program Files (x86)\\Microsoft Visual Studio\\2022\\BuildTools",
-                "C:\\Program Files (x86)\\Microsoft Visual Studio\\2019\\BuildTools",
-                "C:\\Program Files\\Microsoft Visual Studio\\2022\\Community",
-                "C:\\Program Files\\Microsoft Visual Studio\\2019\\Community",
-            ];
-            
-            for vs_path in vs_paths {
-                if Path::new(vs_path).exists() {
-                    return EnvironmentStatus {
-                        status: "ok".to_string(),
-                        version: Some("Found".to_string()),
-                        path: Some(vs_path.to_string()),
-                    };
-                }
-            }
-            
-            EnvironmentStatus {
-                status: "error".to_string(),
-                version: None,
-                path: None,
-            }
+            Some(self.check_linux_deps().await)
         }
         
-        #[cfg(not(target_os = "windows"))]
+        #[cfg(not(target_os = "linux"))]
         {
-            EnvironmentStatus {
-                status: "ok".to_string(),
-                version: Some("Not required on this platform".to_string()),
-                path: None,
-            }
+            None
         }
     }
     
-    async fn check_pake(&self) -> EnvironmentStatus {
-        match which("pake") {
-            Ok(path) => {
-                if let Ok(output) = Command::new("pake").args(&["--version"]).output() {
-                    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    EnvironmentStatus {
-                        status: "ok".to_string(),
-                        version: Some(version),
-                        path: Some(path.to_string_lossy().to_string()),
-                    }
-                } else {
-                    EnvironmentStatus {
-                        status: "error".to_string(),
-                        version: None,
-                        path: None,
-                    }
-                }
-            }
-            Err(_) => EnvironmentStatus {
-                status: "error".to_string(),
-                version: None,
-                path: None,
-            },
+    #[cfg(target_os = "linux")]
+    async fn check_linux_deps(&self) -> EnvironmentStatus {
+        // pkg-config 探测是同步的，放到阻塞线程池中执行
+        let report = match tokio::task::spawn_blocking(|| LinuxDependencyChecker::new().check()).await {
+            Ok(report) => report,
+            Err(e) => return EnvironmentStatus::error(format!("Linux dependency check failed: {}", e)),
+        };
+        if report.all_installed() {
+            return EnvironmentStatus::ok(Some("All installed".to_string()), None);
+        }
+        
+        let status = EnvironmentStatus::error(format!(
+            "Missing system libraries required by Tauri: {}",
+            report.missing().join(", ")
+        ))
+        .with_command("pkg-config --modversion <module>");
+        
+        match report.install_command {
+            Some(command) => status.with_fix(command),
+            None => status.with_fix(format!(
+                "Install with your package manager: {}",
+                report.missing_packages.join(", ")
+            )),
         }
     }
     
-    pub async fn install_tool(&self, tool: &str) -> Result<(), Box<dyn std::error::Error>> {
-        match tool {
-            "nodejs" => {
-                // Open Node.js download page
-                #[cfg(target_os = "windows")]
-                Command::new("cmd").args(&["/c", "start", "https://nodejs.org"]).spawn()?;
-                
-                #[cfg(target_os = "macos")]
-                Command::new("open").args(&["https://nodejs.org"]).spawn()?;
-                
-                #[cfg(target_os = "linux")]
-                Command::new("xdg-open").args(&["https://nodejs.org"]).spawn()?;
-            }
-            "bunjs" => {
-                // Install Bun.js
-                #[cfg(not(target_os = "windows"))]
-                {
-                    Command::new("curl")
-                        .args(&["-fsSL", "https://bun.sh/install"])
-                        .arg("|")
-                        .arg("bash")
-                        .spawn()?;
-                }
-                
-                #[cfg(target_os = "windows")]
-                {
-                    Command::new("powershell")
-                        .args(&["-c", "irm bun.sh/install.ps1 | iex"])
-                        .spawn()?;
-                }
+    /// 查找可执行文件并运行版本命令，区分“不在 PATH 中”、“无法执行”和“执行失败”
+    async fn probe(&self, spec: &ToolSpec) -> EnvironmentStatus {
+        let install_hint = spec.install_hint();
+        
+        let (binary, path) = match spec
+            .binaries
+            .iter()
+            .find_map(|b| self.search_path.which(b).map(|p| (b, p.to_string_lossy().to_string())))
+        {
+            Some(found) => found,
+            None => {
+                return EnvironmentStatus::error(format!(
+                    "`{}` was not found on PATH or in common install locations",
+                    spec.binaries.join("` / `")
+                ))
+                .with_command(format!("{} {}", spec.binaries[0], spec.version_args.join(" ")))
+                .with_fix(install_hint);
             }
-            "rust" => {
-                // Install Rust
-                #[cfg(not(target_os = "windows"))]
-                {
-                    Command::new("curl")
-                        .args(&["--proto", "=https", "--tlsv1.2", "-sSf", "https://sh.rustup.rs"])
-                        .arg("|")
-                        .arg("sh")
-                        .spawn()?;
-                }
-                
-                #[cfg(target_os = "windows")]
-                {
-                    Command::new("cmd")
-                        .args(&["/c", "start", "https://rustup.rs"])
-                        .spawn()?;
-                }
+        };
+        
+        self.run_probe(spec, binary, &path).await
+    }
+    
+    /// 运行版本命令并解析输出
+    async fn run_probe(&self, spec: &ToolSpec, binary: &str, path: &str) -> EnvironmentStatus {
+        let install_hint = spec.install_hint();
+        let path = path.to_string();
+        let command_line = format!("{} {}", binary, spec.version_args.join(" "));
+        
+        let child = AsyncCommand::new(&path)
+            .args(&spec.version_args)
+            .envs(shell_env::imported_vars())
+            .env("PATH", self.search_path.path_env())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        
+        let output = match tokio::time::timeout(PROBE_TIMEOUT, child).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                return EnvironmentStatus::error(format!("Failed to execute `{}`: {}", command_line, e))
+                    .with_command(command_line)
+                    .with_path(path.clone())
+                    .with_fix(format!("Check that {} is executable, or reinstall it", path));
             }
-            "pake" => {
-                // Install pake-cli via bun or bun
-                if which("bun").is_ok() {
-                    Command::new("bun").args(&["install", "-g", "pake-cli"]).spawn()?;
-                } else if which("bun").is_ok() {
-                    Command::new("bun").args(&["install", "-g", "pake-cli"]).spawn()?;
-                } else {
-                    return Err("Neither bun nor bun found".into());
-                }
+            Err(_) => {
+                // 超时后 future 被丢弃，kill_on_drop 会结束子进程
+                return EnvironmentStatus::error(format!(
+                    "`{}` did not finish within {} seconds",
+                    command_line,
+                    PROBE_TIMEOUT.as_secs()
+                ))
+                .with_command(command_line)
+                .with_path(path)
+                .with_fix(format!("The installation may be hanging or broken. {}", install_hint));
             }
-            "visualStudio" => {
-                // Open Visual Studio Build Tools download page
-                #[cfg(target_os = "windows")]
-                Command::new("cmd")
-                    .args(&["/c", "start", "https://visualstudio.microsoft.com/downloads/#build-tools-for-visual-studio-2022"])
-                    .spawn()?;
+        };
+        
+        if !output.status.success() {
+            let code = output
+                .status
+                .code()
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "signal".to_string());
+            return EnvironmentStatus::error(format!("`{}` exited with code {}", command_line, code))
+                .with_command(command_line)
+                .with_path(path)
+                .with_stderr(&output.stderr)
+                .with_fix(format!("The installation looks broken. {}", install_hint));
+        }
+        
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        
+        if let Some(requires) = &spec.requires {
+            let matched = regex::Regex::new(requires)
+                .map(|re| re.is_match(&stdout))
+                .unwrap_or(false);
+            if !matched {
+                return EnvironmentStatus::error(format!("{} is not installed", spec.name))
+                    .with_command(command_line)
+                    .with_path(path)
+                    .with_fix(install_hint);
             }
-            _ => return Err(format!("Unknown tool: {}", tool).into()),
         }
         
-        Ok(())
+        let version = extract_version(&stdout, spec.version_regex.as_deref());
+        match version {
+            Some(version) => EnvironmentStatus::ok(Some(version), Some(path)).with_command(command_line),
+            None if stdout.is_empty() => {
+                EnvironmentStatus::warning(format!("`{}` printed no version information", command_line))
+                    .with_command(command_line)
+                    .with_path(path)
+                    .with_stderr(&output.stderr)
+            }
+            // 无法解析版本号时保留原始输出
+            None => EnvironmentStatus::ok(Some(stdout), Some(path)).with_command(command_line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stderr_of(value: &str) -> Option<String> {
+        EnvironmentStatus::error("failed").with_stderr(value.as_bytes()).stderr
+    }
+
+    #[test]
+    fn stderr_keeps_the_last_characters() {
+        assert_eq!(stderr_of("  \n"), None);
+        assert_eq!(stderr_of(" short error \n").as_deref(), Some("short error"));
+
+        let exact = "e".repeat(MAX_STDERR_LEN);
+        assert_eq!(stderr_of(&exact), Some(exact.clone()));
+
+        // 多字节字符按字符计数，截断后正好保留 MAX_STDERR_LEN 个字符
+        let long = format!("开头{}结尾", "错".repeat(MAX_STDERR_LEN));
+        let kept = stderr_of(&long).unwrap();
+        let tail = kept.strip_prefix('…').unwrap();
+        assert_eq!(tail.chars().count(), MAX_STDERR_LEN);
+        assert!(tail.ends_with("错结尾"));
+        assert!(!tail.contains('头'));
     }
-}
\ No newline at end of file
+}
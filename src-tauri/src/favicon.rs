@@ -0,0 +1,413 @@
+use crate::fetch::Fetcher;
+use crate::icon;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// 下载的网站图标保存在项目目录下的这个子目录中，保存项目时再转换到 icons 目录
+pub const SITE_ICON_DIR: &str = "site-icon";
+
+// 最多尝试下载的候选图标数量
+const MAX_DOWNLOADS: usize = 6;
+// 没有声明尺寸时按常见尺寸估计
+const DEFAULT_FAVICON_SIZE: u32 = 32;
+const DEFAULT_APPLE_TOUCH_ICON_SIZE: u32 = 180;
+// SVG 可以任意缩放，排在所有位图之前
+const SCALABLE_SIZE: u32 = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IconSource {
+    Link,
+    AppleTouchIcon,
+    Manifest,
+    Favicon,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconCandidate {
+    pub url: String,
+    pub source: IconSource,
+    // 页面或清单中声明的尺寸，用于排序
+    pub declared_size: Option<u32>,
+    pub mime: Option<String>,
+    // 清单中只用于 maskable 的图标四周有留白，优先级较低
+    pub maskable: bool,
+}
+
+impl IconCandidate {
+    fn score(&self) -> u32 {
+        let is_svg = self.mime.as_deref() == Some("image/svg+xml") || self.url.to_lowercase().ends_with(".svg");
+        let size = if is_svg {
+            SCALABLE_SIZE
+        } else {
+            self.declared_size.unwrap_or(match self.source {
+                IconSource::AppleTouchIcon => DEFAULT_APPLE_TOUCH_ICON_SIZE,
+                _ => DEFAULT_FAVICON_SIZE,
+            })
+        };
+        if self.maskable {
+            size / 2
+        } else {
+            size
+        }
+    }
+}
+
+/// 保存到项目中的网站图标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteIcon {
+    pub path: String,
+    pub url: String,
+    pub source: IconSource,
+    pub width: u32,
+    pub height: u32,
+    pub warnings: Vec<String>,
+}
+
+/// HTML 中的 <link> 标签，href 已按页面地址解析为绝对地址
+#[derive(Debug, Clone)]
+pub struct HtmlLink {
+    pub rel: Vec<String>,
+    pub href: String,
+    pub sizes: Option<String>,
+    pub mime: Option<String>,
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("invalid built-in regex"))
+}
+
+/// 解析标签中的属性，属性名转为小写
+pub fn tag_attributes(tag: &str) -> HashMap<String, String> {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    let attribute = regex(
+        &ATTRIBUTE,
+        r#"(?s)([A-Za-z_:][-A-Za-z0-9_:.]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#,
+    );
+    attribute
+        .captures_iter(tag)
+        .map(|caps| {
+            let value = caps.get(2).or(caps.get(3)).or(caps.get(4)).map_or("", |m| m.as_str());
+            (caps[1].to_lowercase(), decode_entities(value.trim()))
+        })
+        .collect()
+}
+
+/// 属性值中常见的 HTML 实体
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+/// 匹配指定名称的所有开始标签
+pub fn find_tags<'a>(html: &'a str, name: &str) -> Vec<&'a str> {
+    let pattern = format!(r"(?is)<{}\b[^>]*>", regex::escape(name));
+    Regex::new(&pattern)
+        .map(|re| re.find_iter(html).map(|m| m.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// 相对地址的基准：页面中有 <base href> 时使用它，否则使用页面地址
+pub fn base_url(html: &str, page_url: &url::Url) -> url::Url {
+    find_tags(html, "base")
+        .into_iter()
+        .find_map(|tag| tag_attributes(tag).remove("href"))
+        .and_then(|href| page_url.join(&href).ok())
+        .unwrap_or_else(|| page_url.clone())
+}
+
+pub fn links(html: &str, page_url: &url::Url) -> Vec<HtmlLink> {
+    let base = base_url(html, page_url);
+    find_tags(html, "link")
+        .into_iter()
+        .filter_map(|tag| {
+            let mut attributes = tag_attributes(tag);
+            let href = base.join(&attributes.remove("href")?).ok()?;
+            Some(HtmlLink {
+                rel: attributes
+                    .remove("rel")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_lowercase)
+                    .collect(),
+                href: href.to_string(),
+                sizes: attributes.remove("sizes"),
+                mime: attributes.remove("type").map(|mime| mime.to_lowercase()),
+            })
+        })
+        .collect()
+}
+
+/// sizes 属性中最大的尺寸，"any" 表示可缩放
+fn parse_sizes(sizes: &str) -> Option<u32> {
+    sizes
+        .split_whitespace()
+        .filter_map(|size| {
+            if size.eq_ignore_ascii_case("any") {
+                return Some(SCALABLE_SIZE);
+            }
+            let size = size.to_lowercase();
+            let (width, height) = size.split_once('x')?;
+            Some(width.parse::<u32>().ok()?.min(height.parse().ok()?))
+        })
+        .max()
+}
+
+/// 清单文件中的图标，src 按清单地址解析
+pub fn manifest_icons(manifest: &Value, manifest_url: &url::Url) -> Vec<IconCandidate> {
+    let Some(icons) = manifest.get("icons").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    icons
+        .iter()
+        .filter_map(|icon| {
+            let src = manifest_url.join(icon.get("src")?.as_str()?).ok()?;
+            let purpose = icon.get("purpose").and_then(Value::as_str).unwrap_or("any").to_lowercase();
+            let purposes: Vec<&str> = purpose.split_whitespace().collect();
+            // 单色图标只有轮廓，不适合作为应用图标
+            if purposes == ["monochrome"] {
+                return None;
+            }
+            Some(IconCandidate {
+                url: src.to_string(),
+                source: IconSource::Manifest,
+                declared_size: icon.get("sizes").and_then(Value::as_str).and_then(parse_sizes),
+                mime: icon.get("type").and_then(Value::as_str).map(str::to_lowercase),
+                maskable: !purposes.contains(&"any"),
+            })
+        })
+        .collect()
+}
+
+/// 加载后的页面和它引用的 web app manifest
+pub struct SitePage {
+    // 跟随重定向之后的地址
+    pub url: url::Url,
+    pub html: String,
+    pub links: Vec<HtmlLink>,
+    pub manifest: Option<Value>,
+    pub manifest_url: Option<url::Url>,
+}
+
+impl SitePage {
+    pub async fn load<F: Fetcher>(fetcher: &F, page_url: &str) -> Result<Self, String> {
+        let page = fetcher.get(page_url).await?;
+        if !page.is_success() {
+            return Err(format!("{} returned HTTP {}", page_url, page.status));
+        }
+        // 使用重定向后的地址解析相对路径
+        let url = url::Url::parse(&page.url).map_err(|e| format!("Invalid URL {}: {}", page.url, e))?;
+        let html = page.text();
+        let links = links(&html, &url);
+
+        // 清单获取失败不影响页面中的其他信息
+        let mut manifest = None;
+        let mut manifest_url = None;
+        let href = links.iter().find(|link| link.rel.iter().any(|rel| rel == "manifest"));
+        if let Some(href) = href {
+            if let Ok(response) = fetcher.get(&href.href).await {
+                let parsed = serde_json::from_slice::<Value>(&response.body).ok();
+                if let (true, Some(parsed), Ok(base)) = (response.is_success(), parsed, url::Url::parse(&response.url)) {
+                    manifest = Some(parsed);
+                    manifest_url = Some(base);
+                }
+            }
+        }
+
+        Ok(Self {
+            url,
+            html,
+            links,
+            manifest,
+            manifest_url,
+        })
+    }
+
+    /// 清单中的字符串字段
+    pub fn manifest_str(&self, key: &str) -> Option<String> {
+        self.manifest
+            .as_ref()?
+            .get(key)?
+            .as_str()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+
+    /// 页面的 <link> 标签、web app manifest 和 /favicon.ico 中的候选图标，按清晰度从高到低排序
+    pub fn icon_candidates(&self) -> Vec<IconCandidate> {
+        let mut candidates = Vec::new();
+        for link in &self.links {
+            let source = if link.rel.iter().any(|rel| rel == "apple-touch-icon" || rel == "apple-touch-icon-precomposed") {
+                IconSource::AppleTouchIcon
+            } else if link.rel.iter().any(|rel| rel == "icon") {
+                IconSource::Link
+            } else {
+                continue;
+            };
+            candidates.push(IconCandidate {
+                url: link.href.clone(),
+                source,
+                declared_size: link.sizes.as_deref().and_then(parse_sizes),
+                mime: link.mime.clone(),
+                maskable: false,
+            });
+        }
+
+        if let (Some(manifest), Some(manifest_url)) = (&self.manifest, &self.manifest_url) {
+            candidates.extend(manifest_icons(manifest, manifest_url));
+        }
+
+        if let Ok(favicon) = self.url.join("/favicon.ico") {
+            candidates.push(IconCandidate {
+                url: favicon.to_string(),
+                source: IconSource::Favicon,
+                declared_size: None,
+                mime: None,
+                maskable: false,
+            });
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        candidates.retain(|candidate| seen.insert(candidate.url.clone()));
+        // sort_by_key 是稳定排序，分数相同时保留页面中的顺序
+        candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score()));
+        candidates
+    }
+}
+
+/// 下载分数最高的几个候选图标，选实际尺寸最大的保存到 dir 中
+pub async fn download_best<F: Fetcher>(fetcher: &F, page_url: &str, dir: &Path) -> Result<SiteIcon, String> {
+    let candidates = SitePage::load(fetcher, page_url).await?.icon_candidates();
+
+    let mut best: Option<(IconCandidate, icon::ImageInfo, Vec<u8>)> = None;
+    for candidate in candidates.into_iter().take(MAX_DOWNLOADS) {
+        let Ok(response) = fetcher.get(&candidate.url).await else { continue };
+        // 有些网站对不存在的图标返回 200 和一个 HTML 页面
+        if !response.is_success() || response.mime().is_some_and(|mime| mime.starts_with("text/")) {
+            continue;
+        }
+        // 无法解码的格式（例如 WebP、GIF）直接跳过
+        let Some(info) = icon::inspect(&response.body) else { continue };
+
+        let size = info.width.min(info.height);
+        if best.as_ref().is_none_or(|(_, current, _)| size > current.width.min(current.height)) {
+            best = Some((candidate, info, response.body));
+        }
+        if size >= icon::RECOMMENDED_SIZE {
+            break;
+        }
+    }
+
+    let (candidate, info, data) = best.ok_or_else(|| format!("No usable icon found on {}", page_url))?;
+    let warnings = icon::validate_size(info.width, info.height)?;
+
+    // 只保留最新下载的图标
+    if dir.exists() {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("icon.{}", info.extension));
+    std::fs::write(&path, data).map_err(|e| format!("Failed to save icon: {}", e))?;
+
+    Ok(SiteIcon {
+        path: path.to_string_lossy().to_string(),
+        url: candidate.url,
+        source: candidate.source,
+        width: info.width,
+        height: info.height,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::stub::{png, StubFetcher};
+
+    const PAGE: &str = "https://example.com/";
+
+    const HTML: &str = r#"<html><head>
+        <link rel="icon" href="/small.png" sizes="16x16">
+        <link rel="apple-touch-icon" href="/apple.png">
+        <link rel="manifest" href="/app/manifest.json">
+    </head></html>"#;
+
+    const MANIFEST: &str = r#"{"icons": [
+        {"src": "icon-192.png", "sizes": "192x192"},
+        {"src": "maskable-512.png", "sizes": "512x512", "purpose": "maskable"},
+        {"src": "icon-512.png", "sizes": "512x512", "type": "image/png"},
+        {"src": "mono.png", "sizes": "512x512", "purpose": "monochrome"}
+    ]}"#;
+
+    fn site() -> StubFetcher {
+        StubFetcher::default()
+            .serve(PAGE, "text/html; charset=utf-8", HTML)
+            .serve("https://example.com/app/manifest.json", "application/manifest+json", MANIFEST)
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pake-gui-favicon-{}-{}", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn candidates_are_ordered_by_declared_size() {
+        let page = SitePage::load(&site(), PAGE).await.unwrap();
+        let urls: Vec<String> = page.icon_candidates().into_iter().map(|c| c.url).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/app/icon-512.png",
+                "https://example.com/app/maskable-512.png",
+                "https://example.com/app/icon-192.png",
+                "https://example.com/apple.png",
+                "https://example.com/favicon.ico",
+                "https://example.com/small.png",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn download_best_picks_largest_valid_icon() {
+        // 声明 512 的图标实际是 HTML 页面，声明 192 的实际只有 128，apple-touch-icon 实际是 300
+        let fetcher = site()
+            .serve("https://example.com/app/icon-512.png", "text/html", "<html></html>")
+            .serve("https://example.com/app/maskable-512.png", "image/png", png(200, 200))
+            .serve("https://example.com/app/icon-192.png", "image/png", png(128, 128))
+            .serve("https://example.com/apple.png", "image/png", png(300, 300))
+            .serve("https://example.com/small.png", "image/png", png(16, 16));
+        let dir = temp_dir("best");
+
+        let icon = download_best(&fetcher, PAGE, &dir).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(icon.url, "https://example.com/apple.png");
+        assert_eq!(icon.source, IconSource::AppleTouchIcon);
+        assert_eq!((icon.width, icon.height), (300, 300));
+        assert!(icon.path.ends_with("icon.png"));
+    }
+
+    #[tokio::test]
+    async fn download_best_stops_at_recommended_size() {
+        let fetcher = site()
+            .serve("https://example.com/app/icon-512.png", "image/png", png(512, 512))
+            .serve("https://example.com/apple.png", "image/png", png(1024, 1024));
+        let dir = temp_dir("stop");
+
+        let icon = download_best(&fetcher, PAGE, &dir).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(icon.url, "https://example.com/app/icon-512.png");
+        assert!(icon.warnings.is_empty());
+    }
+}
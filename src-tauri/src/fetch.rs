@@ -0,0 +1,147 @@
+use std::future::Future;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+// 网页、清单和图标都不大，超过这个大小的响应直接放弃
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 10;
+const USER_AGENT: &str = concat!("Mozilla/5.0 (compatible; pake-gui/", env!("CARGO_PKG_VERSION"), ")");
+
+#[derive(Debug, Clone)]
+pub struct FetchResponse {
+    // 跟随重定向之后的最终地址
+    pub url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl FetchResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+
+    /// 不带参数的 MIME 类型，例如 "image/png"
+    pub fn mime(&self) -> Option<String> {
+        self.content_type
+            .as_deref()
+            .and_then(|value| value.split(';').next())
+            .map(|mime| mime.trim().to_lowercase())
+            .filter(|mime| !mime.is_empty())
+    }
+}
+
+/// 获取网页和图标使用的 HTTP 客户端，可以替换成指向本地服务器或固定数据的实现
+pub trait Fetcher: Send + Sync {
+    fn get(&self, url: &str) -> impl Future<Output = Result<FetchResponse, String>> + Send;
+}
+
+pub struct HttpFetcher {
+    client: reqwest::Client,
+}
+
+impl HttpFetcher {
+    pub fn new() -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(TIMEOUT)
+            .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { client })
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    async fn get(&self, url: &str) -> Result<FetchResponse, String> {
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+
+        let final_url = response.url().to_string();
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", url, e))?
+        {
+            if body.len() + chunk.len() > MAX_BODY_BYTES {
+                return Err(format!("Response from {} is larger than {} MB", url, MAX_BODY_BYTES / 1024 / 1024));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(FetchResponse {
+            url: final_url,
+            status,
+            content_type,
+            body,
+        })
+    }
+}
+
+/// 测试中使用的固定响应，没有登记的地址返回 404
+#[cfg(test)]
+pub mod stub {
+    use super::{FetchResponse, Fetcher};
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    pub struct StubFetcher {
+        responses: HashMap<String, FetchResponse>,
+    }
+
+    impl StubFetcher {
+        pub fn serve(mut self, url: &str, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+            let response = FetchResponse {
+                url: url.to_string(),
+                status: 200,
+                content_type: Some(content_type.to_string()),
+                body: body.into(),
+            };
+            self.responses.insert(url.to_string(), response);
+            self
+        }
+
+        /// from 重定向到已登记的 to
+        pub fn redirect(mut self, from: &str, to: &str) -> Self {
+            let response = self.responses.get(to).cloned().expect("redirect target is not served");
+            self.responses.insert(from.to_string(), response);
+            self
+        }
+    }
+
+    impl Fetcher for StubFetcher {
+        async fn get(&self, url: &str) -> Result<FetchResponse, String> {
+            Ok(self.responses.get(url).cloned().unwrap_or_else(|| FetchResponse {
+                url: url.to_string(),
+                status: 404,
+                content_type: Some("text/html".to_string()),
+                body: Vec::new(),
+            }))
+        }
+    }
+
+    /// 指定尺寸的 PNG 图片
+    pub fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::new(width, height)
+            .write_to(&mut data, image::ImageFormat::Png)
+            .expect("failed to encode PNG");
+        data.into_inner()
+    }
+}
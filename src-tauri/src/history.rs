@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 每个项目只保留最近的构建记录
+const MAX_RECORDS: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+/// 一次构建的记录，环境变量中的密钥已经脱敏
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRecord {
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub command: String,
+    #[serde(default)]
+    pub env: Vec<RecordedEnvVar>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 项目目录下的 build-history.json，最新的记录在前
+pub struct BuildHistory {
+    path: PathBuf,
+}
+
+impl BuildHistory {
+    pub fn new(project_dir: &Path) -> Self {
+        Self {
+            path: project_dir.join("build-history.json"),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<BuildRecord>, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn append(&self, record: BuildRecord) -> Result<(), Box<dyn std::error::Error>> {
+        let mut records = self.load().unwrap_or_default();
+        records.insert(0, record);
+        records.truncate(MAX_RECORDS);
+        fs::write(&self.path, serde_json::to_string_pretty(&records)?)?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,260 @@
+use crate::local_app;
+use crate::pake_config::PakeConfig;
+use crate::tools::Platform;
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::imageops::FilterType;
+use image::{ExtendedColorType, ImageFormat, RgbaImage};
+use std::error::Error;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+// 生成的图标保存在项目目录下的这个子目录中
+pub const ICONS_DIR: &str = "icons";
+
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["png", "svg", "jpg", "jpeg", "ico", "icns"];
+
+// 小于这个尺寸的图片放大后会非常模糊
+const MIN_SOURCE_SIZE: u32 = 64;
+// macOS 的 .icns 最大需要 1024，低于 512 时给出提示
+pub const RECOMMENDED_SIZE: u32 = 512;
+// SVG 按这个尺寸渲染
+const SVG_RENDER_SIZE: u32 = 1024;
+
+const ICO_SIZES: &[u32] = &[16, 24, 32, 48, 64, 128, 256];
+const PNG_SIZES: &[u32] = &[32, 128, 256, 512];
+// .icns 中每种 OSType 对应的像素尺寸，@2x 的条目使用双倍尺寸
+const ICNS_ENTRIES: &[(&[u8; 4], u32)] = &[
+    (b"icp4", 16),
+    (b"icp5", 32),
+    (b"icp6", 64),
+    (b"ic07", 128),
+    (b"ic08", 256),
+    (b"ic09", 512),
+    (b"ic10", 1024),
+    (b"ic11", 32),
+    (b"ic12", 64),
+    (b"ic13", 256),
+    (b"ic14", 512),
+];
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 图标为空、是远程地址或已经是项目 icons 目录中生成的文件时不需要处理
+fn needs_import(icon: &str, project_dir: &Path) -> bool {
+    !icon.is_empty()
+        && !icon.starts_with("http://")
+        && !icon.starts_with("https://")
+        && !local_app::resolve_path(icon, project_dir).starts_with(project_dir.join(ICONS_DIR))
+}
+
+/// 解码后的图片格式和尺寸
+#[derive(Debug, Clone, Copy)]
+pub struct ImageInfo {
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 识别下载的图片数据，不支持的格式返回 None；SVG 按渲染尺寸计算
+pub fn inspect(data: &[u8]) -> Option<ImageInfo> {
+    if resvg::usvg::Tree::from_data(data, &resvg::usvg::Options::default()).is_ok() {
+        return Some(ImageInfo {
+            extension: "svg",
+            width: SVG_RENDER_SIZE,
+            height: SVG_RENDER_SIZE,
+        });
+    }
+
+    let format = image::guess_format(data).ok()?;
+    let extension = match format {
+        ImageFormat::Png => "png",
+        ImageFormat::Jpeg => "jpg",
+        ImageFormat::Ico => "ico",
+        _ => return None,
+    };
+    let image = image::load_from_memory_with_format(data, format).ok()?;
+    Some(ImageInfo {
+        extension,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// 读取源图标并转换成 RGBA 图像，ICO 取其中最大的一张
+fn load(path: &Path) -> Result<RgbaImage, Box<dyn Error>> {
+    match extension(path).as_str() {
+        "svg" => render_svg(path),
+        "png" | "jpg" | "jpeg" | "ico" => Ok(image::open(path)?.to_rgba8()),
+        ext => Err(format!("Unsupported icon format .{}, use .png, .svg, .jpg or .ico", ext).into()),
+    }
+}
+
+fn render_svg(path: &Path) -> Result<RgbaImage, Box<dyn Error>> {
+    let data = fs::read(path)?;
+    let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default())?;
+    let size = tree.size();
+    let scale = SVG_RENDER_SIZE as f32 / size.width().max(size.height());
+    let width = ((size.width() * scale).round() as u32).max(1);
+    let height = ((size.height() * scale).round() as u32).max(1);
+
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height).ok_or("Invalid SVG size")?;
+    resvg::render(&tree, resvg::tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    // tiny-skia 使用预乘 alpha，转换回普通 RGBA
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Ok(RgbaImage::from_raw(width, height, pixels).ok_or("Failed to render SVG")?)
+}
+
+/// 检查图标能否使用，返回尺寸等方面的提示；错误对应 icon 字段
+pub fn check(icon: &str, project_dir: &Path) -> Result<Vec<String>, String> {
+    if !needs_import(icon, project_dir) {
+        return Ok(Vec::new());
+    }
+
+    let path = local_app::resolve_path(icon, project_dir);
+    if !path.is_file() {
+        return Err(format!("Icon not found: {}", path.display()));
+    }
+
+    let ext = extension(&path);
+    if !SUPPORTED_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(format!("Unsupported icon format .{}, use .png, .svg, .jpg or .ico", ext));
+    }
+    // .icns 无法解码转换，只能原样用于 macOS
+    if ext == "icns" {
+        if Platform::current() == Platform::Macos {
+            return Ok(Vec::new());
+        }
+        return Err("An .icns icon can only be used on macOS, use a .png or .svg instead".to_string());
+    }
+
+    let image = load(&path).map_err(|e| format!("Failed to read icon {}: {}", path.display(), e))?;
+    validate_size(image.width(), image.height())
+}
+
+pub fn validate_size(width: u32, height: u32) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+    if width.min(height) < MIN_SOURCE_SIZE {
+        return Err(format!(
+            "Icon is {}x{}, it must be at least {}x{}",
+            width, height, MIN_SOURCE_SIZE, MIN_SOURCE_SIZE
+        ));
+    }
+    if width != height {
+        warnings.push(format!("Icon is {}x{}, it will be centered on a square canvas", width, height));
+    }
+    if width.max(height) < RECOMMENDED_SIZE {
+        warnings.push(format!(
+            "Icon is {}x{}, use at least {}x{} to avoid blurry icons",
+            width, height, RECOMMENDED_SIZE, RECOMMENDED_SIZE
+        ));
+    }
+    Ok(warnings)
+}
+
+/// 非正方形的图片居中放到透明的正方形画布上
+fn square(image: RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if width == height {
+        return image;
+    }
+    let side = width.max(height);
+    let mut canvas = RgbaImage::new(side, side);
+    image::imageops::overlay(&mut canvas, &image, ((side - width) / 2).into(), ((side - height) / 2).into());
+    canvas
+}
+
+fn resized(image: &RgbaImage, size: u32) -> RgbaImage {
+    image::imageops::resize(image, size, size, FilterType::Lanczos3)
+}
+
+fn png_bytes(image: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+fn write_ico(image: &RgbaImage, path: &Path) -> Result<(), Box<dyn Error>> {
+    let frames = ICO_SIZES
+        .iter()
+        .map(|&size| IcoFrame::as_png(resized(image, size).as_raw(), size, size, ExtendedColorType::Rgba8))
+        .collect::<Result<Vec<_>, _>>()?;
+    IcoEncoder::new(fs::File::create(path)?).encode_images(&frames)?;
+    Ok(())
+}
+
+/// .icns 由 8 字节的文件头和若干 PNG 条目组成，每个条目前有类型和长度
+fn write_icns(image: &RgbaImage, path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut body = Vec::new();
+    for (os_type, size) in ICNS_ENTRIES {
+        let png = png_bytes(&resized(image, *size))?;
+        body.extend_from_slice(*os_type);
+        body.extend_from_slice(&(png.len() as u32 + 8).to_be_bytes());
+        body.extend_from_slice(&png);
+    }
+
+    let mut icns = Vec::with_capacity(body.len() + 8);
+    icns.extend_from_slice(b"icns");
+    icns.extend_from_slice(&(body.len() as u32 + 8).to_be_bytes());
+    icns.extend_from_slice(&body);
+    fs::write(path, icns)?;
+    Ok(())
+}
+
+/// 当前平台上 pake 使用的图标文件
+fn platform_icon(dir: &Path) -> PathBuf {
+    match Platform::current() {
+        Platform::Macos => dir.join("icon.icns"),
+        Platform::Windows => dir.join("icon.ico"),
+        Platform::Linux => dir.join("icon.png"),
+    }
+}
+
+/// 由源图标生成各平台需要的 .ico、.icns 和各尺寸的 .png，
+/// icon 改为项目内当前平台使用的文件，原始路径记录在 iconSource 中
+pub fn import(config: &mut PakeConfig, project_dir: &Path) -> Result<(), Box<dyn Error>> {
+    if config.icon.is_empty() {
+        config.icon_source.clear();
+        return Ok(());
+    }
+    if !needs_import(&config.icon, project_dir) {
+        return Ok(());
+    }
+
+    check(&config.icon, project_dir)?;
+    let source = local_app::resolve_path(&config.icon, project_dir);
+    let dir = project_dir.join(ICONS_DIR);
+    fs::create_dir_all(&dir)?;
+
+    let target = if extension(&source) == "icns" {
+        let target = dir.join("icon.icns");
+        fs::copy(&source, &target)?;
+        target
+    } else {
+        let image = square(load(&source)?);
+        for size in PNG_SIZES {
+            resized(&image, *size).save_with_format(dir.join(format!("{}x{}.png", size, size)), ImageFormat::Png)?;
+        }
+        resized(&image, RECOMMENDED_SIZE).save_with_format(dir.join("icon.png"), ImageFormat::Png)?;
+        write_ico(&image, &dir.join("icon.ico"))?;
+        write_icns(&image, &dir.join("icon.icns"))?;
+        platform_icon(&dir)
+    };
+
+    let relative = target.strip_prefix(project_dir).unwrap_or(&target);
+    config.icon = relative.to_string_lossy().replace('\\', "/");
+    config.icon_source = source.to_string_lossy().to_string();
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use crate::local_app;
+use crate::pake_config::PakeConfig;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 项目的注入文件保存在项目目录下的这个子目录中，配置中使用相对路径
+pub const INJECT_DIR: &str = "inject";
+
+// pake 只支持注入 CSS 和 JS
+const ALLOWED_EXTENSIONS: &[&str] = &["css", "js"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectFile {
+    pub name: String,
+    // 相对于项目目录的路径，即写入 inject 配置的值
+    pub path: String,
+    pub size: u64,
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 检查注入文件的类型，只允许 .css 和 .js
+pub fn check_type(path: &Path) -> Result<(), String> {
+    if ALLOWED_EXTENSIONS.contains(&extension(path).as_str()) {
+        Ok(())
+    } else {
+        Err(format!("Inject files must be .css or .js: {}", path.display()))
+    }
+}
+
+/// 项目 inject 目录中的文件
+pub struct InjectStore {
+    dir: PathBuf,
+}
+
+impl InjectStore {
+    pub fn new(project_dir: &Path) -> Self {
+        Self {
+            dir: project_dir.join(INJECT_DIR),
+        }
+    }
+
+    /// 文件名不能包含路径，避免写到 inject 目录之外；Windows 上的盘符和备用数据流也用冒号，一并拒绝
+    fn file_path(&self, name: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let name = name.trim();
+        let plain = Path::new(name).file_name() == Some(OsStr::new(name));
+        if !plain || name.starts_with('.') || name.contains(['/', '\\', ':']) {
+            return Err(format!("Invalid inject file name: {:?}", name).into());
+        }
+        let path = self.dir.join(name);
+        check_type(&path)?;
+        Ok(path)
+    }
+
+    fn relative(name: &str) -> String {
+        format!("{}/{}", INJECT_DIR, name)
+    }
+
+    pub fn list(&self) -> Result<Vec<InjectFile>, Box<dyn Error>> {
+        let mut files = Vec::new();
+        if !self.dir.exists() {
+            return Ok(files);
+        }
+
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !path.is_file() || check_type(&path).is_err() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            files.push(InjectFile {
+                path: Self::relative(&name),
+                size: entry.metadata()?.len(),
+                name,
+            });
+        }
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(files)
+    }
+
+    pub fn read(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        Ok(fs::read_to_string(self.file_path(name)?)?)
+    }
+
+    /// 新建或覆盖一个内联编写的注入文件
+    pub fn write(&self, name: &str, content: &str) -> Result<InjectFile, Box<dyn Error>> {
+        let path = self.file_path(name)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, content)?;
+        Ok(InjectFile {
+            name: name.trim().to_string(),
+            path: Self::relative(name.trim()),
+            size: content.len() as u64,
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let path = self.file_path(name)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// 把项目外的文件复制到 inject 目录，重名时在文件名后加序号
+    pub fn import(&self, source: &Path) -> Result<InjectFile, Box<dyn Error>> {
+        check_type(source)?;
+        if !source.is_file() {
+            return Err(format!("Inject file not found: {}", source.display()).into());
+        }
+        fs::create_dir_all(&self.dir)?;
+
+        let stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let ext = extension(source);
+        let mut name = format!("{}.{}", stem, ext);
+        let mut index = 1;
+        while self.dir.join(&name).exists() {
+            // 同一个文件再次导入时直接覆盖
+            if fs::read(self.dir.join(&name))? == fs::read(source)? {
+                break;
+            }
+            index += 1;
+            name = format!("{}-{}.{}", stem, index, ext);
+        }
+
+        fs::copy(source, self.dir.join(&name))?;
+        self.list()?
+            .into_iter()
+            .find(|file| file.name == name)
+            .ok_or_else(|| format!("Failed to import {}", source.display()).into())
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        path.parent().is_some_and(|parent| parent == self.dir)
+    }
+}
+
+/// 检查 inject 中的每个文件，返回的错误对应 inject 字段
+pub fn check(config: &PakeConfig, project_dir: &Path) -> Result<(), String> {
+    for file in &config.inject {
+        let path = local_app::resolve_path(file, project_dir);
+        check_type(&path)?;
+        if !path.is_file() {
+            return Err(format!("Inject file not found: {}", path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// 把项目外的注入文件复制到 inject 目录并改为相对路径，移动项目目录后仍然可用
+pub fn import(config: &mut PakeConfig, project_dir: &Path) -> Result<(), Box<dyn Error>> {
+    check(config, project_dir)?;
+    let store = InjectStore::new(project_dir);
+
+    let mut inject = Vec::with_capacity(config.inject.len());
+    for file in &config.inject {
+        let path = local_app::resolve_path(file, project_dir);
+        let relative = if store.contains(&path) {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            InjectStore::relative(&name)
+        } else {
+            store.import(&path)?.path
+        };
+        if !inject.contains(&relative) {
+            inject.push(relative);
+        }
+    }
+    config.inject = inject;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pake-gui-inject-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn file_path_rejects_paths_and_other_types() {
+        let store = InjectStore::new(Path::new("project"));
+        assert_eq!(store.file_path(" style.css ").unwrap(), Path::new("project").join(INJECT_DIR).join("style.css"));
+        assert!(store.file_path("app.JS").is_ok());
+        for name in ["../x.css", "sub/x.css", "sub\\x.css", "C:x.js", "x.js:stream", ".hidden.js", "", "x.html", "x"] {
+            assert!(store.file_path(name).is_err(), "{:?} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn import_reuses_same_file_and_renames_on_collision() {
+        let root = temp_dir("import");
+        let store = InjectStore::new(&root.join("project"));
+        for dir in ["a", "b"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("a").join("style.css"), "body { color: red; }").unwrap();
+        fs::write(root.join("b").join("style.css"), "body { color: blue; }").unwrap();
+
+        let first = store.import(&root.join("a").join("style.css")).unwrap();
+        let again = store.import(&root.join("a").join("style.css")).unwrap();
+        let other = store.import(&root.join("b").join("style.css")).unwrap();
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|file| file.name).collect();
+        let renamed = store.read("style-2.css").unwrap();
+        let rejected = store.import(&root.join("a").join("missing.css")).is_err();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(first.path, "inject/style.css");
+        assert_eq!(again.path, "inject/style.css");
+        assert_eq!(other.path, "inject/style-2.css");
+        assert_eq!(names, ["style-2.css", "style.css"]);
+        assert_eq!(renamed, "body { color: blue; }");
+        assert!(rejected);
+    }
+}
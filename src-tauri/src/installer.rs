@@ -0,0 +1,364 @@
+use crate::environment::{EnvironmentChecker, EnvironmentStatus};
+use crate::linux_deps::LinuxDependencyChecker;
+use crate::search_path::SearchPath;
+use crate::shell_env;
+use crate::settings::SettingsManager;
+use crate::toolchain::{validate_install_version, ManagedToolchain, ManagedVersion, PAKE_BINARY, PAKE_PACKAGE};
+use crate::tools::{global_install_args, local_install_args, package_spec, InstallStrategy, ToolRegistry, ToolSpec};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command as AsyncCommand;
+use which::which;
+
+/// 一次安装实际要执行的动作
+pub enum InstallAction {
+    Run(InstallCommand),
+    OpenUrl(String),
+    // 已经安装，不需要执行任何操作
+    AlreadyInstalled,
+}
+
+pub struct InstallCommand {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl InstallCommand {
+    fn new(program: PathBuf, args: Vec<String>) -> Self {
+        Self { program, args }
+    }
+
+    /// 通过系统 shell 执行脚本，这样脚本中的管道等语法才能生效
+    fn shell(script: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if cfg!(target_os = "windows") {
+            let args = ["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", script];
+            Ok(Self::new(which("powershell")?, args.iter().map(|s| s.to_string()).collect()))
+        } else {
+            Ok(Self::new(which("sh")?, vec!["-c".to_string(), script.to_string()]))
+        }
+    }
+
+    pub fn display(&self) -> String {
+        let program = self
+            .program
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.program.to_string_lossy().to_string());
+        format!("{} {}", program, self.args.join(" "))
+    }
+}
+
+/// 前端可以指定的安装选项，只对通过包管理器安装的工具生效
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallOptions {
+    // 指定包管理器（bun / npm / pnpm），为空时按工具定义的顺序自动选择
+    #[serde(default)]
+    pub package_manager: Option<String>,
+    // 指定版本，例如 "3.1.1"，为空时安装最新版
+    #[serde(default)]
+    pub version: Option<String>,
+    // 安装到 GUI 管理的目录而不是全局
+    #[serde(default)]
+    pub managed: bool,
+}
+
+/// pake-cli 缓存版本的安装来源：本地 tarball 或（镜像）registry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PakeSource {
+    // 本地 tarball 路径，例如 /path/to/pake-cli-3.1.1.tgz
+    #[serde(default)]
+    pub tarball: Option<String>,
+    // 覆盖设置中的 registry 镜像
+    #[serde(default)]
+    pub registry: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallOutput {
+    pub tool: String,
+    // "stdout" 或 "stderr"
+    pub stream: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallResult {
+    pub tool: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    // 实际执行的命令；只打开了下载页面时为空
+    pub command: Option<String>,
+    pub opened_url: Option<String>,
+    // 安装结束后重新检查得到的状态
+    pub status: EnvironmentStatus,
+}
+
+pub struct Installer {
+    registry: ToolRegistry,
+    search_path: SearchPath,
+}
+
+impl Installer {
+    pub fn new() -> Self {
+        Self {
+            registry: ToolRegistry::load(),
+            search_path: SearchPath::load(),
+        }
+    }
+
+    /// 根据工具的安装策略决定要执行的命令
+    pub fn plan(&self, tool: &str, options: &InstallOptions) -> Result<InstallAction, Box<dyn std::error::Error>> {
+        if tool == "linuxDeps" {
+            // 只有 Linux 上构建需要这些系统依赖，其他平台上不检测也不安装
+            if !cfg!(target_os = "linux") {
+                return Err("Linux system dependencies are only needed on Linux".into());
+            }
+            // 系统依赖需要 root 权限安装，这里只给出对应发行版的安装命令
+            let report = LinuxDependencyChecker::new().check();
+            if report.missing_packages.is_empty() {
+                return Ok(InstallAction::AlreadyInstalled);
+            }
+            return Err(match report.install_command {
+                Some(command) => format!("Please run: {}", command),
+                None => format!(
+                    "Please install the following packages with your package manager: {}",
+                    report.missing_packages.join(", ")
+                ),
+            }
+            .into());
+        }
+
+        let spec = self.registry.get(tool).ok_or_else(|| format!("Unknown tool: {}", tool))?;
+
+        match &spec.install {
+            InstallStrategy::Download => Self::download_page(spec),
+            InstallStrategy::Script { unix, windows } => {
+                let script = if cfg!(target_os = "windows") { windows } else { unix };
+                match script {
+                    Some(script) => Ok(InstallAction::Run(InstallCommand::shell(script)?)),
+                    None => Self::download_page(spec),
+                }
+            }
+            InstallStrategy::GlobalPackage { package, managers } => {
+                let (manager, program) = match &options.package_manager {
+                    Some(manager) => {
+                        // 只接受工具定义中列出的包管理器，其他程序的安装参数格式未知
+                        if !managers.contains(manager) {
+                            return Err(format!(
+                                "Unsupported package manager {}, use one of {}",
+                                manager,
+                                managers.join(", ")
+                            )
+                            .into());
+                        }
+                        let program = self
+                            .search_path
+                            .which(manager)
+                            .ok_or_else(|| format!("{} was not found on PATH", manager))?;
+                        (manager, program)
+                    }
+                    // 按顺序使用第一个可用的包管理器
+                    None => managers
+                        .iter()
+                        .find_map(|m| self.search_path.which(m).map(|path| (m, path)))
+                        .ok_or_else(|| format!("None of {} found on PATH", managers.join(", ")))?,
+                };
+                
+                if let Some(version) = options.version.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                    validate_install_version(version)?;
+                }
+                let package = package_spec(package, options.version.as_deref());
+                let args = if options.managed {
+                    let dir = ManagedToolchain::new()?.ensure_package_dir(&package_name(&package))?;
+                    local_install_args(manager, &package, &dir.to_string_lossy())
+                } else {
+                    global_install_args(manager, &package)
+                };
+                Ok(InstallAction::Run(InstallCommand::new(program, args[1..].to_vec())))
+            }
+            InstallStrategy::RustupTarget { target } => {
+                let program = self.search_path.which("rustup").ok_or("rustup was not found on PATH")?;
+                let args = vec!["target".to_string(), "add".to_string(), target.clone()];
+                Ok(InstallAction::Run(InstallCommand::new(program, args)))
+            }
+            InstallStrategy::None => Err(format!("{} cannot be installed automatically", spec.name).into()),
+        }
+    }
+
+    fn download_page(spec: &ToolSpec) -> Result<InstallAction, Box<dyn std::error::Error>> {
+        spec.homepage
+            .clone()
+            .map(InstallAction::OpenUrl)
+            .ok_or_else(|| format!("{} has no download page", spec.name).into())
+    }
+
+    /// 执行安装，逐行回调输出，结束后重新检查该工具
+    pub async fn install<F>(
+        &self,
+        tool: &str,
+        options: &InstallOptions,
+        on_output: F,
+    ) -> Result<InstallResult, Box<dyn std::error::Error>>
+    where
+        F: Fn(InstallOutput) + Send + Sync,
+    {
+        let action = self.plan(tool, options)?;
+        let (success, exit_code, command, opened_url) = match action {
+            InstallAction::OpenUrl(url) => {
+                open_url(&url)?;
+                (true, None, None, Some(url))
+            }
+            InstallAction::AlreadyInstalled => (true, None, None, None),
+            InstallAction::Run(install_command) => {
+                let status = self.run_streaming(tool, &install_command, &on_output).await?;
+                (status.success(), status.code(), Some(install_command.display()), None)
+            }
+        };
+
+        let status = EnvironmentChecker::new().check_tool(tool).await?;
+
+        Ok(InstallResult {
+            tool: tool.to_string(),
+            success,
+            exit_code,
+            command,
+            opened_url,
+            status,
+        })
+    }
+
+    /// 把指定版本的 pake-cli 安装到版本缓存中，用于项目固定版本构建
+    pub async fn install_pake_version<F>(
+        &self,
+        version: Option<&str>,
+        source: &PakeSource,
+        on_output: F,
+    ) -> Result<ManagedVersion, Box<dyn std::error::Error>>
+    where
+        F: Fn(InstallOutput) + Send + Sync,
+    {
+        // dist-tag 安装后按 package.json 中的实际版本号缓存，项目固定的是这个版本号
+        if let Some(version) = version.map(str::trim).filter(|v| !v.is_empty()) {
+            validate_install_version(version)?;
+        }
+        let registry = match &source.registry {
+            Some(registry) => Some(registry.clone()),
+            None => SettingsManager::new()?.load()?.pake_registry,
+        };
+        let package = match &source.tarball {
+            Some(tarball) => {
+                if !std::path::Path::new(tarball).is_file() {
+                    return Err(format!("Tarball not found: {}", tarball).into());
+                }
+                tarball.clone()
+            }
+            None => package_spec(PAKE_PACKAGE, version),
+        };
+
+        // npm 对 --prefix 和 tarball 的支持最好，优先使用
+        let (manager, program) = ["npm", "pnpm", "bun"]
+            .iter()
+            .find_map(|m| self.search_path.which(m).map(|path| (*m, path)))
+            .ok_or("None of npm, pnpm, bun found on PATH")?;
+
+        let toolchain = ManagedToolchain::new()?;
+        let staging = toolchain.create_staging_dir(PAKE_PACKAGE)?;
+
+        let mut args = local_install_args(manager, &package, &staging.to_string_lossy());
+        if let Some(registry) = registry.filter(|r| !r.trim().is_empty()) {
+            args.push("--registry".to_string());
+            args.push(registry.trim().to_string());
+        }
+        let install_command = InstallCommand::new(program, args[1..].to_vec());
+
+        let status = self.run_streaming("pake", &install_command, &on_output).await;
+        let result = match status {
+            Ok(status) if status.success() => toolchain.promote_staging_dir(PAKE_PACKAGE, PAKE_BINARY, &staging),
+            Ok(status) => Err(format!(
+                "`{}` exited with code {}",
+                install_command.display(),
+                status.code().map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string())
+            )
+            .into()),
+            Err(e) => Err(e),
+        };
+
+        // 失败时清理临时目录
+        if staging.exists() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    /// 启动安装命令并逐行转发 stdout / stderr
+    async fn run_streaming<F>(
+        &self,
+        tool: &str,
+        install_command: &InstallCommand,
+        on_output: &F,
+    ) -> Result<ExitStatus, Box<dyn std::error::Error>>
+    where
+        F: Fn(InstallOutput),
+    {
+        let mut child = AsyncCommand::new(&install_command.program)
+            .args(&install_command.args)
+            .envs(shell_env::imported_vars())
+            .env("PATH", self.search_path.path_env())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start `{}`: {}", install_command.display(), e))?;
+
+        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+        futures::join!(
+            forward_lines(tool, "stdout", stdout, on_output),
+            forward_lines(tool, "stderr", stderr, on_output),
+        );
+
+        Ok(child.wait().await?)
+    }
+}
+
+/// 去掉版本号后的包名，兼容 @scope/name@version 的写法
+fn package_name(package: &str) -> String {
+    match package.rfind('@') {
+        Some(index) if index > 0 => package[..index].to_string(),
+        _ => package.to_string(),
+    }
+}
+
+async fn forward_lines<R, F>(tool: &str, stream: &str, reader: R, on_output: &F)
+where
+    R: AsyncRead + Unpin,
+    F: Fn(InstallOutput),
+{
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        on_output(InstallOutput {
+            tool: tool.to_string(),
+            stream: stream.to_string(),
+            line,
+        });
+    }
+}
+
+fn open_url(url: &str) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(target_os = "windows")]
+    Command::new("cmd").args(["/c", "start", "", url]).spawn()?;
+
+    #[cfg(target_os = "macos")]
+    Command::new("open").arg(url).spawn()?;
+
+    #[cfg(target_os = "linux")]
+    Command::new("xdg-open").arg(url).spawn()?;
+
+    Ok(())
+}
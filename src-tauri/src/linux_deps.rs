@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::process::Command;
+use which::which;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Distro {
+    Debian,
+    Fedora,
+    Arch,
+    Unknown,
+}
+
+impl Distro {
+    /// 根据 /etc/os-release 的 ID 与 ID_LIKE 推断发行版家族
+    pub fn detect() -> Self {
+        match fs::read_to_string("/etc/os-release") {
+            Ok(content) => Self::from_os_release(&content),
+            Err(_) => Distro::Unknown,
+        }
+    }
+
+    fn from_os_release(content: &str) -> Self {
+        let mut ids = Vec::new();
+        for line in content.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                if key == "ID" || key == "ID_LIKE" {
+                    let value = value.trim().trim_matches('"').to_lowercase();
+                    ids.extend(value.split_whitespace().map(|s| s.to_string()));
+                }
+            }
+        }
+
+        for id in &ids {
+            match id.as_str() {
+                "debian" | "ubuntu" | "linuxmint" | "pop" | "elementary" | "zorin" => return Distro::Debian,
+                "fedora" | "rhel" | "centos" | "rocky" | "almalinux" => return Distro::Fedora,
+                "arch" | "manjaro" | "endeavouros" | "garuda" => return Distro::Arch,
+                _ => {}
+            }
+        }
+
+        Distro::Unknown
+    }
+
+    fn install_command(&self, packages: &[String]) -> Option<String> {
+        if packages.is_empty() {
+            return None;
+        }
+
+        match self {
+            Distro::Debian => Some(format!("sudo apt install -y {}", packages.join(" "))),
+            Distro::Fedora => {
+                // build-essential 在 Fedora 上对应的是软件包组，需要单独安装
+                let (groups, plain): (Vec<&String>, Vec<&String>) =
+                    packages.iter().partition(|p| p.starts_with('@'));
+                let mut commands = Vec::new();
+                if !plain.is_empty() {
+                    let plain: Vec<&str> = plain.iter().map(|s| s.as_str()).collect();
+                    commands.push(format!("sudo dnf install -y {}", plain.join(" ")));
+                }
+                for group in groups {
+                    commands.push(format!("sudo dnf group install -y \"{}\"", group.trim_start_matches('@')));
+                }
+                Some(commands.join(" && "))
+            }
+            Distro::Arch => Some(format!("sudo pacman -S --needed {}", packages.join(" "))),
+            Distro::Unknown => None,
+        }
+    }
+}
+
+enum Probe {
+    PkgConfig(&'static str),
+    Binaries(&'static [&'static str]),
+}
+
+struct LinuxDependency {
+    name: &'static str,
+    probe: Probe,
+    debian: &'static [&'static str],
+    fedora: &'static [&'static str],
+    arch: &'static [&'static str],
+}
+
+// Tauri 在 Linux 上构建所需的系统依赖，参考 https://v2.tauri.app/start/prerequisites/
+const DEPENDENCIES: &[LinuxDependency] = &[
+    LinuxDependency {
+        name: "webkit2gtk-4.1",
+        probe: Probe::PkgConfig("webkit2gtk-4.1"),
+        debian: &["libwebkit2gtk-4.1-dev"],
+        fedora: &["webkit2gtk4.1-devel"],
+        arch: &["webkit2gtk-4.1"],
+    },
+    LinuxDependency {
+        name: "libayatana-appindicator",
+        probe: Probe::PkgConfig("ayatana-appindicator3-0.1"),
+        debian: &["libayatana-appindicator3-dev"],
+        fedora: &["libappindicator-gtk3-devel"],
+        arch: &["libappindicator-gtk3"],
+    },
+    LinuxDependency {
+        name: "librsvg",
+        probe: Probe::PkgConfig("librsvg-2.0"),
+        debian: &["librsvg2-dev"],
+        fedora: &["librsvg2-devel"],
+        arch: &["librsvg"],
+    },
+    LinuxDependency {
+        name: "openssl",
+        probe: Probe::PkgConfig("openssl"),
+        debian: &["libssl-dev"],
+        fedora: &["openssl-devel"],
+        arch: &["openssl"],
+    },
+    LinuxDependency {
+        name: "build-essential",
+        probe: Probe::Binaries(&["cc", "make"]),
+        debian: &["build-essential"],
+        fedora: &["@c-development"],
+        arch: &["base-devel"],
+    },
+    LinuxDependency {
+        name: "pkg-config",
+        probe: Probe::Binaries(&["pkg-config"]),
+        debian: &["pkg-config"],
+        fedora: &["pkgconf-pkg-config"],
+        arch: &["pkgconf"],
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistroPackages {
+    pub debian: Vec<String>,
+    pub fedora: Vec<String>,
+    pub arch: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub installed: bool,
+    pub version: Option<String>,
+    pub packages: DistroPackages,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinuxDependencyReport {
+    pub distro: Distro,
+    pub dependencies: Vec<DependencyStatus>,
+    pub missing_packages: Vec<String>,
+    pub install_command: Option<String>,
+}
+
+impl LinuxDependencyReport {
+    pub fn all_installed(&self) -> bool {
+        self.dependencies.iter().all(|d| d.installed)
+    }
+
+    pub fn missing(&self) -> Vec<&str> {
+        self.dependencies
+            .iter()
+            .filter(|d| !d.installed)
+            .map(|d| d.name.as_str())
+            .collect()
+    }
+}
+
+pub struct LinuxDependencyChecker;
+
+impl LinuxDependencyChecker {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn check(&self) -> LinuxDependencyReport {
+        let distro = Distro::detect();
+        let has_pkg_config = which("pkg-config").is_ok();
+
+        let mut dependencies = Vec::new();
+        let mut missing_packages = Vec::new();
+
+        for dep in DEPENDENCIES {
+            let (installed, version) = match dep.probe {
+                Probe::PkgConfig(module) if has_pkg_config => Self::probe_pkg_config(module),
+                // 没有 pkg-config 时无法确认开发头文件是否存在，按缺失处理
+                Probe::PkgConfig(_) => (false, None),
+                Probe::Binaries(binaries) => (binaries.iter().all(|b| which(b).is_ok()), None),
+            };
+
+            let packages = DistroPackages {
+                debian: dep.debian.iter().map(|s| s.to_string()).collect(),
+                fedora: dep.fedora.iter().map(|s| s.to_string()).collect(),
+                arch: dep.arch.iter().map(|s| s.to_string()).collect(),
+            };
+
+            if !installed {
+                match distro {
+                    Distro::Debian => missing_packages.extend(packages.debian.iter().cloned()),
+                    Distro::Fedora => missing_packages.extend(packages.fedora.iter().cloned()),
+                    Distro::Arch => missing_packages.extend(packages.arch.iter().cloned()),
+                    // 无法识别的发行版只能给出依赖名称，由用户自行查找对应的软件包
+                    Distro::Unknown => missing_packages.push(dep.name.to_string()),
+                }
+            }
+
+            dependencies.push(DependencyStatus {
+                name: dep.name.to_string(),
+                installed,
+                version,
+                packages,
+            });
+        }
+
+        let install_command = distro.install_command(&missing_packages);
+
+        LinuxDependencyReport {
+            distro,
+            dependencies,
+            missing_packages,
+            install_command,
+        }
+    }
+
+    fn probe_pkg_config(module: &str) -> (bool, Option<String>) {
+        match Command::new("pkg-config").args(["--modversion", module]).output() {
+            Ok(output) if output.status.success() => {
+                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                (true, Some(version))
+            }
+            _ => (false, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_distro_family_from_os_release() {
+        assert_eq!(Distro::from_os_release("NAME=\"Ubuntu\"\nID=ubuntu\nID_LIKE=debian\n"), Distro::Debian);
+        assert_eq!(Distro::from_os_release("ID=\"rocky\"\nID_LIKE=\"rhel centos fedora\"\n"), Distro::Fedora);
+        assert_eq!(Distro::from_os_release("ID=manjaro\nID_LIKE=arch\n"), Distro::Arch);
+        // 衍生发行版只能通过 ID_LIKE 识别
+        assert_eq!(Distro::from_os_release("ID=neon\nID_LIKE=\"ubuntu debian\"\n"), Distro::Debian);
+        assert_eq!(Distro::from_os_release("ID=opensuse-tumbleweed\nID_LIKE=\"opensuse suse\"\n"), Distro::Unknown);
+        assert_eq!(Distro::from_os_release("VERSION_ID=1\nDISTRIB_ID=ubuntu\n"), Distro::Unknown);
+        assert_eq!(Distro::from_os_release(""), Distro::Unknown);
+    }
+
+    #[test]
+    fn every_dependency_has_packages_for_each_distro() {
+        for dep in DEPENDENCIES {
+            for list in [dep.debian, dep.fedora, dep.arch] {
+                assert!(!list.is_empty(), "{} has no package", dep.name);
+                assert!(list.iter().all(|package| !package.trim().is_empty() && !package.contains(' ')));
+            }
+        }
+    }
+
+    #[test]
+    fn install_command_per_distro() {
+        let debian: Vec<String> = DEPENDENCIES.iter().flat_map(|dep| packages(dep.debian)).collect();
+        assert_eq!(
+            Distro::Debian.install_command(&debian).unwrap(),
+            "sudo apt install -y libwebkit2gtk-4.1-dev libayatana-appindicator3-dev librsvg2-dev libssl-dev build-essential pkg-config"
+        );
+        assert_eq!(
+            Distro::Fedora.install_command(&packages(&["openssl-devel", "@c-development"])).unwrap(),
+            "sudo dnf install -y openssl-devel && sudo dnf group install -y \"c-development\""
+        );
+        assert_eq!(
+            Distro::Fedora.install_command(&packages(&["@c-development"])).unwrap(),
+            "sudo dnf group install -y \"c-development\""
+        );
+        assert_eq!(
+            Distro::Arch.install_command(&packages(&["webkit2gtk-4.1", "base-devel"])).unwrap(),
+            "sudo pacman -S --needed webkit2gtk-4.1 base-devel"
+        );
+        assert_eq!(Distro::Unknown.install_command(&packages(&["openssl"])), None);
+        assert_eq!(Distro::Debian.install_command(&[]), None);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::pake_config::PakeConfig;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 本地应用的文件复制到项目目录下的这个子目录中
+pub const APP_DIR: &str = "app";
+
+// 打包整个目录时依次查找的入口文件
+const INDEX_FILES: &[&str] = &["index.html", "index.htm"];
+
+// 避免误选主目录之类的大目录时复制大量文件
+const MAX_COPY_BYTES: u64 = 512 * 1024 * 1024;
+
+// 复制目录时跳过的条目
+const SKIPPED_ENTRIES: &[&str] = &[".git", ".DS_Store", "node_modules"];
+
+/// url 不是 http(s) 地址时按本地文件或目录处理
+pub fn is_local(config: &PakeConfig) -> bool {
+    let url = config.url.as_str();
+    !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://")
+}
+
+/// 展开开头的 ~，相对路径按项目目录解析
+pub fn resolve_path(path: &str, project_dir: &Path) -> PathBuf {
+    let path = path.trim();
+    let expanded = match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+        None if path == "~" => dirs::home_dir(),
+        None => None,
+    };
+    let path = expanded.unwrap_or_else(|| PathBuf::from(path));
+
+    if path.is_absolute() {
+        path
+    } else {
+        project_dir.join(path)
+    }
+}
+
+/// 要复制到项目中的内容：整个目录或单个文件，以及入口文件相对于复制根的路径
+struct LocalSource {
+    root: PathBuf,
+    entry: String,
+    is_dir: bool,
+}
+
+fn locate(config: &PakeConfig, project_dir: &Path) -> Result<LocalSource, String> {
+    let path = resolve_path(&config.url, project_dir);
+    if !path.exists() {
+        return Err(format!("Local file or directory not found: {}", path.display()));
+    }
+
+    if path.is_dir() {
+        let entry = INDEX_FILES
+            .iter()
+            .find(|name| path.join(name).is_file())
+            .ok_or_else(|| format!("No index.html found in {}", path.display()))?;
+        return Ok(LocalSource {
+            root: path,
+            entry: entry.to_string(),
+            is_dir: true,
+        });
+    }
+
+    // 选择单个文件时只复制这个文件，需要一并打包引用的资源时应选择所在目录
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid local file path: {}", path.display()))?;
+    Ok(LocalSource {
+        root: path,
+        entry: file_name,
+        is_dir: false,
+    })
+}
+
+/// 复制的目录包含项目目录时，复制会进入自己生成的 app.tmp 目录
+fn contains_project(root: &Path, project_dir: &Path) -> bool {
+    match (root.canonicalize(), project_dir.canonicalize()) {
+        (Ok(root), Ok(project_dir)) => project_dir.starts_with(root),
+        _ => project_dir.starts_with(root),
+    }
+}
+
+/// 检查本地文件或目录是否可以打包，返回的错误对应 url 字段
+pub fn check(config: &PakeConfig, project_dir: &Path) -> Result<(), String> {
+    if !is_local(config) || is_imported(config, project_dir) {
+        return Ok(());
+    }
+
+    let source = locate(config, project_dir)?;
+    if source.is_dir {
+        if contains_project(&source.root, project_dir) {
+            return Err(format!(
+                "{} contains the project directory, choose a directory with only the app files",
+                source.root.display()
+            ));
+        }
+        let size = dir_size(&source.root).map_err(|e| format!("Failed to read {}: {}", source.root.display(), e))?;
+        if size > MAX_COPY_BYTES {
+            return Err(format!(
+                "{} is larger than {} MB, choose a smaller directory",
+                source.root.display(),
+                MAX_COPY_BYTES / 1024 / 1024
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// url 已经指向项目 app 目录中的文件
+fn is_imported(config: &PakeConfig, project_dir: &Path) -> bool {
+    let path = resolve_path(&config.url, project_dir);
+    let app_dir = project_dir.join(APP_DIR);
+    match (path.canonicalize(), app_dir.canonicalize()) {
+        (Ok(path), Ok(app_dir)) => path.starts_with(app_dir),
+        _ => path.starts_with(app_dir),
+    }
+}
+
+/// 把本地文件或目录复制到项目的 app 目录，url 改为项目内的入口文件，
+/// 原始位置记录在 localSource 中，之后的构建不再依赖原始文件
+pub fn import(config: &mut PakeConfig, project_dir: &Path) -> Result<(), Box<dyn Error>> {
+    if !is_local(config) {
+        config.local_source.clear();
+        return Ok(());
+    }
+    if is_imported(config, project_dir) {
+        return Ok(());
+    }
+
+    check(config, project_dir)?;
+    let source = locate(config, project_dir)?;
+    let original = resolve_path(&config.url, project_dir);
+
+    // 先复制到临时目录，成功后再替换旧的 app 目录
+    let app_dir = project_dir.join(APP_DIR);
+    let staging = project_dir.join(format!("{}.tmp", APP_DIR));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let copied = if source.is_dir {
+        copy_dir(&source.root, &staging)
+    } else {
+        fs::copy(&source.root, staging.join(&source.entry)).map(|_| ())
+    };
+    if let Err(e) = copied {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!("Failed to copy {} into the project: {}", source.root.display(), e).into());
+    }
+
+    if app_dir.exists() {
+        fs::remove_dir_all(&app_dir)?;
+    }
+    fs::rename(&staging, &app_dir)?;
+
+    config.url = format!("{}/{}", APP_DIR, source.entry);
+    config.local_source = original.to_string_lossy().to_string();
+    // 打包整个目录时需要 pake 一并打包入口文件引用的资源
+    if source.is_dir {
+        config.use_local_file = true;
+    }
+    Ok(())
+}
+
+/// pake 在项目目录中运行，本地应用、图标和注入文件传入绝对路径避免它按其他目录解析
+pub fn resolve_for_build(config: &mut PakeConfig, project_dir: &Path) {
+    if is_local(config) {
+        config.url = resolve_path(&config.url, project_dir).to_string_lossy().to_string();
+    }
+    for icon in [&mut config.icon, &mut config.system_tray_icon] {
+        if !icon.is_empty() && !icon.starts_with("http://") && !icon.starts_with("https://") {
+            *icon = resolve_path(icon, project_dir).to_string_lossy().to_string();
+        }
+    }
+    for file in &mut config.inject {
+        *file = resolve_path(file, project_dir).to_string_lossy().to_string();
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if SKIPPED_ENTRIES.iter().any(|skipped| name == *skipped) {
+            continue;
+        }
+
+        // 跟随符号链接，但不进入链接的目录，避免循环
+        let path = entry.path();
+        let metadata = fs::metadata(&path)?;
+        if metadata.is_dir() {
+            if !entry.file_type()?.is_symlink() {
+                copy_dir(&path, &to.join(&name))?;
+            }
+        } else {
+            fs::copy(&path, to.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if SKIPPED_ENTRIES.iter().any(|skipped| entry.file_name() == *skipped) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if !file_type.is_symlink() {
+            size += entry.metadata()?.len();
+        }
+        if size > MAX_COPY_BYTES {
+            break;
+        }
+    }
+    Ok(size)
+}
+
+/// pake 生成的应用名称：设置了名称时使用名称，本地应用使用原始文件或目录名
+pub fn output_name(config: &PakeConfig) -> String {
+    if !config.name.is_empty() {
+        return config.name.clone();
+    }
+
+    if is_local(config) {
+        let source = if config.local_source.is_empty() { &config.url } else { &config.local_source };
+        let path = Path::new(source);
+        let stem = if path.extension().is_some() { path.file_stem() } else { path.file_name() };
+        if let Some(stem) = stem {
+            return stem.to_string_lossy().to_string();
+        }
+    } else if let Ok(url) = url::Url::parse(&config.url) {
+        // pake 没有名称时使用域名的主体部分，例如 www.github.com -> github
+        if let Some(host) = url.host_str() {
+            let parts: Vec<&str> = host.split('.').collect();
+            let name = if parts.len() >= 2 { parts[parts.len() - 2] } else { host };
+            return name.to_string();
+        }
+    }
+    "app".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pake-gui-local-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn local_config(url: &Path, use_local_file: bool) -> PakeConfig {
+        PakeConfig {
+            url: url.to_string_lossy().to_string(),
+            use_local_file,
+            ..PakeConfig::default()
+        }
+    }
+
+    #[test]
+    fn rejects_directory_containing_project() {
+        let root = temp_dir("nested");
+        fs::write(root.join("index.html"), "<html></html>").unwrap();
+        let project_dir = root.join(".pake-gui").join("projects").join("demo");
+        fs::create_dir_all(&project_dir).unwrap();
+
+        let mut config = local_config(&root, true);
+        let result = import(&mut config, &project_dir).map_err(|e| e.to_string());
+        let staged = project_dir.join("app.tmp").exists();
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(result.unwrap_err().contains("contains the project directory"));
+        assert!(!staged);
+    }
+
+    #[test]
+    fn lone_file_is_copied_without_siblings() {
+        let source = temp_dir("file-source");
+        fs::write(source.join("page.html"), "<html></html>").unwrap();
+        fs::write(source.join("other.txt"), "unrelated").unwrap();
+        let project_dir = temp_dir("file-project");
+
+        let mut config = local_config(&source.join("page.html"), true);
+        let result = import(&mut config, &project_dir).map_err(|e| e.to_string());
+        let copied: Vec<String> = fs::read_dir(project_dir.join(APP_DIR))
+            .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+            .unwrap_or_default();
+        let _ = fs::remove_dir_all(&source);
+        let _ = fs::remove_dir_all(&project_dir);
+
+        result.unwrap();
+        assert_eq!(copied, ["page.html"]);
+        assert_eq!(config.url, "app/page.html");
+    }
+}
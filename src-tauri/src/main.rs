@@ -1,8 +1,32 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod doctor;
+mod env_cache;
 mod environment;
+mod favicon;
+mod fetch;
+mod history;
+mod icon;
+mod inject;
+mod installer;
+mod linux_deps;
+mod local_app;
+mod pake_capabilities;
+mod pake_config;
+mod preflight;
 mod project;
+mod project_env;
+mod safe_domain;
+mod search_path;
+mod settings;
+mod shell_env;
+mod shortcut;
+mod site_probe;
+mod snippets;
+mod toolchain;
+mod tools;
+mod validation;
 
 use commands::*;
 
@@ -11,6 +35,13 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(env_cache::EnvironmentCache::default())
+        .setup(|app| {
+            // 从桌面启动时进程环境不完整，后台读取登录 shell 的环境变量
+            shell_env::init();
+            env_cache::watch(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_projects,
             load_project,
@@ -20,8 +51,36 @@ fn main() {
             get_project_config_path,
             get_project_output_path,
             check_environment,
+            check_tool,
+            check_linux_dependencies,
             install_tool,
+            list_pake_versions,
+            install_pake_version,
+            remove_pake_version,
+            get_tool_specs,
+            save_custom_tool,
+            remove_custom_tool,
+            get_path_settings,
+            save_path_settings,
+            get_effective_environment,
+            get_build_history,
+            clear_build_history,
+            generate_doctor_report,
             update_pake_config,
+            validate_config,
+            fetch_site_icon,
+            probe_url,
+            list_inject_files,
+            read_inject_file,
+            save_inject_file,
+            delete_inject_file,
+            import_inject_file,
+            list_snippets,
+            save_snippet,
+            delete_snippet,
+            get_snippet_usage,
+            check_shortcut,
+            validate_project,
             build_pake_app,
             open_path
         ])
@@ -0,0 +1,280 @@
+use crate::pake_config::PakeConfig;
+use crate::search_path::SearchPath;
+use crate::shell_env;
+use crate::toolchain::ManagedToolchain;
+use crate::tools::{compare_versions, extract_version};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command as AsyncCommand;
+
+// 已知版本的 `pake --help` 输出，按版本从低到高排列；
+// 无法运行 pake --help 时使用不高于所装版本的最近一份，比最旧的一份还旧时没有可用的选项表
+const FIXTURES: &[(&str, &str)] = &[
+    ("2.3.7", include_str!("../fixtures/pake-help/2.3.7.txt")),
+    ("3.0.0", include_str!("../fixtures/pake-help/3.0.0.txt")),
+    ("3.1.1", include_str!("../fixtures/pake-help/3.1.1.txt")),
+];
+
+const HELP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CapabilitySource {
+    // 解析所装 pake 的 --help 输出
+    Help,
+    // 内置的已知版本选项表
+    Fixture,
+}
+
+/// 某个 pake-cli 版本支持的选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PakeCapabilities {
+    pub version: String,
+    pub source: CapabilitySource,
+    pub flags: BTreeSet<String>,
+    // 版本在内置选项表覆盖的范围内，表中没有的选项可以确定不支持
+    pub authoritative: bool,
+}
+
+impl PakeCapabilities {
+    /// 参数中的选项是否被支持，不是长选项时返回 true
+    pub fn supports(&self, arg: &str) -> bool {
+        match option_name(arg) {
+            Some(name) => self.flags.contains(name),
+            None => true,
+        }
+    }
+
+    /// 参数中这个版本不支持的选项
+    pub fn unsupported<'a>(&self, args: &'a [String]) -> Vec<&'a str> {
+        args.iter()
+            .filter(|arg| !self.supports(arg))
+            .filter_map(|arg| option_name(arg))
+            .collect()
+    }
+}
+
+// --name=value 取 --name
+fn option_name(arg: &str) -> Option<&str> {
+    arg.split('=').next().filter(|name| name.starts_with("--") && name.len() > 2)
+}
+
+/// 从 commander 生成的帮助信息中取出 Options 部分的长选项
+pub fn parse_help(help: &str) -> BTreeSet<String> {
+    let mut flags = BTreeSet::new();
+    let mut in_options = false;
+    for line in help.lines() {
+        let trimmed = line.trim();
+        if trimmed.ends_with(':') && !line.starts_with(' ') {
+            in_options = trimmed.eq_ignore_ascii_case("Options:");
+            continue;
+        }
+        if !in_options || !trimmed.starts_with('-') {
+            continue;
+        }
+        // 选项和说明之间至少隔两个空格，说明中出现的选项名不算
+        let usage = trimmed.split("  ").next().unwrap_or_default();
+        for token in usage.split([',', ' ']) {
+            if let Some(name) = option_name(token) {
+                flags.insert(name.to_string());
+            }
+        }
+    }
+    flags
+}
+
+/// 不高于指定版本的最近一份内置选项表；版本比所有内置版本都旧或无法比较时返回空表
+fn fixture(version: &str) -> BTreeSet<String> {
+    FIXTURES
+        .iter()
+        .rev()
+        .find(|(fixture_version, _)| compare_versions(fixture_version, version).is_some_and(|o| o != Ordering::Greater))
+        .map(|(_, help)| parse_help(help))
+        .unwrap_or_default()
+}
+
+fn is_covered(version: &str) -> bool {
+    let (first, _) = FIXTURES[0];
+    let (last, _) = FIXTURES[FIXTURES.len() - 1];
+    compare_versions(version, first).is_some_and(|o| o != Ordering::Less)
+        && compare_versions(version, last).is_some_and(|o| o != Ordering::Greater)
+}
+
+/// 由 --help 输出生成选项表；pake 用 hideHelp 隐藏了部分选项，同一版本的内置选项表中的选项也算支持，
+/// 合并了内置选项表时不能确定帮助信息是否完整，不作为确定的结论
+pub fn from_help(version: &str, help: &str) -> PakeCapabilities {
+    let mut flags = parse_help(help);
+    let fixture = fixture(version);
+    let merged = !fixture.is_subset(&flags);
+    flags.extend(fixture);
+    PakeCapabilities {
+        version: version.to_string(),
+        source: CapabilitySource::Help,
+        flags,
+        authoritative: is_covered(version) && !merged,
+    }
+}
+
+/// 无法运行 pake --help 时使用的内置选项表
+pub fn from_fixture(version: &str) -> PakeCapabilities {
+    PakeCapabilities {
+        version: version.to_string(),
+        source: CapabilitySource::Fixture,
+        flags: fixture(version),
+        authoritative: false,
+    }
+}
+
+/// 按版本缓存的选项表，保存在 ~/.pake-gui/pake-capabilities.json，每个版本只解析一次 --help
+pub struct CapabilityCache {
+    path: PathBuf,
+}
+
+impl CapabilityCache {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let mut dir = dirs::home_dir().ok_or("Could not find home directory")?;
+        dir.push(".pake-gui");
+
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+        }
+
+        Ok(Self {
+            path: dir.join("pake-capabilities.json"),
+        })
+    }
+
+    fn load(&self) -> BTreeMap<String, PakeCapabilities> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, version: &str) -> Option<PakeCapabilities> {
+        self.load().remove(version)
+    }
+
+    pub fn insert(&self, capabilities: &PakeCapabilities) -> Result<(), Box<dyn Error>> {
+        let mut entries = self.load();
+        entries.insert(capabilities.version.clone(), capabilities.clone());
+        fs::write(&self.path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+}
+
+// 运行 pake 并返回 stdout，失败或超时时返回 None
+async fn run_pake(pake: &Path, arg: &str, search_path: &SearchPath) -> Option<String> {
+    let child = AsyncCommand::new(pake)
+        .arg(arg)
+        .envs(shell_env::imported_vars())
+        .env("PATH", search_path.path_env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(HELP_TIMEOUT, child).await.ok()?.ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 项目构建时使用的 pake 支持的选项；找不到 pake 或无法确定版本时返回 None，由 preflight 报告缺失的工具
+pub async fn detect(config: &PakeConfig, search_path: &SearchPath) -> Option<PakeCapabilities> {
+    let pake = ManagedToolchain::new()
+        .and_then(|toolchain| toolchain.resolve_pake(config.pinned_version(), search_path))
+        .ok()?;
+
+    // 固定版本时不需要再运行 pake --version
+    let version = match config.pinned_version() {
+        Some(version) => version.to_string(),
+        None => extract_version(&run_pake(&pake, "--version", search_path).await?, None)?,
+    };
+
+    let cache = CapabilityCache::new().ok();
+    if let Some(cached) = cache.as_ref().and_then(|cache| cache.get(&version)) {
+        return Some(cached);
+    }
+
+    match run_pake(&pake, "--help", search_path).await.filter(|help| !parse_help(help).is_empty()) {
+        Some(help) => {
+            let capabilities = from_help(&version, &help);
+            if let Some(cache) = &cache {
+                let _ = cache.insert(&capabilities);
+            }
+            Some(capabilities)
+        }
+        None => Some(from_fixture(&version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn help(version: &str) -> &'static str {
+        FIXTURES.iter().find(|(v, _)| *v == version).map(|(_, help)| *help).unwrap()
+    }
+
+    #[test]
+    fn parses_fixture_options() {
+        let flags = parse_help(help("2.3.7"));
+        assert!(flags.contains("--iter-copy-file"));
+        assert!(flags.contains("--version"));
+        assert!(!flags.contains("--use-local-file"));
+        assert!(!flags.contains("--incognito"));
+
+        let flags = parse_help(help("3.0.0"));
+        assert!(flags.contains("--use-local-file"));
+        assert!(flags.contains("--hide-on-close"));
+        assert!(!flags.contains("--incognito"));
+
+        let flags = parse_help(help("3.1.1"));
+        assert!(flags.contains("--incognito"));
+    }
+
+    #[test]
+    fn fixture_uses_nearest_older_version() {
+        assert!(fixture("3.0.5").contains("--use-local-file"));
+        assert!(!fixture("3.0.5").contains("--incognito"));
+        assert!(fixture("4.0.0").contains("--incognito"));
+        assert!(fixture("2.0.0").is_empty());
+        assert!(!from_fixture("2.0.0").authoritative);
+    }
+
+    #[test]
+    fn unsupported_options_for_config() {
+        let config = PakeConfig {
+            url: "https://example.com".to_string(),
+            name: "Example".to_string(),
+            use_local_file: true,
+            incognito: true,
+            hide_on_close: true,
+            extra_args: vec!["--transparent=true".to_string()],
+            ..PakeConfig::default()
+        };
+        let args = config.to_args();
+
+        let capabilities = from_help("3.0.0", help("3.0.0"));
+        assert!(capabilities.authoritative);
+        assert_eq!(capabilities.unsupported(&args), ["--incognito", "--transparent"]);
+
+        let capabilities = from_help("2.3.7", help("2.3.7"));
+        assert_eq!(capabilities.unsupported(&args), ["--use-local-file", "--hide-on-close", "--incognito"]);
+    }
+
+    #[test]
+    fn merged_fixture_is_not_authoritative() {
+        // 帮助信息不完整时用内置选项表补上，但不能据此确定不支持
+        let capabilities = from_help("3.1.1", "Options:\n  --name <string>  Application name\n");
+        assert!(capabilities.flags.contains("--incognito"));
+        assert!(!capabilities.authoritative);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::safe_domain;
+use crate::shortcut::Accelerator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const DEFAULT_WIDTH: u32 = 1200;
+pub const DEFAULT_HEIGHT: u32 = 780;
+
+// 表单中的选项对应的 pake 参数，extraArgs 中不应再设置
+pub const KNOWN_FLAGS: &[&str] = &[
+    "--name", "--icon", "--width", "--height", "--use-local-file", "--fullscreen", "--hide-title-bar",
+    "--multi-arch", "--debug", "--activation-shortcut", "--always-on-top", "--targets", "--user-agent",
+    "--show-system-tray", "--system-tray-icon", "--inject", "--safe-domain", "--transparent",
+    "--dark-mode", "--disabled-web-shortcuts", "--app-version", "--installer-language", "--proxy-url", "--title",
+    "--hide-on-close", "--incognito",
+];
+
+// 旧版前端在图标为空时填入的占位路径，相对于项目目录并不存在，按未设置处理
+const LEGACY_DEFAULT_ICON: &str = "../src-tauri/icons/icon.ico";
+
+/// 项目配置中传给 pake 的部分，字段与前端 config 对象一一对应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PakeConfig {
+    pub url: String,
+    pub name: String,
+    pub icon: String,
+    // 生成项目图标前的原始图片
+    pub icon_source: String,
+    pub width: u32,
+    pub height: u32,
+    pub use_local_file: bool,
+    // 本地应用复制到项目目录之前的原始位置
+    pub local_source: String,
+    pub fullscreen: bool,
+    pub hide_title_bar: bool,
+    pub multi_arch: bool,
+    pub debug: bool,
+    pub activation_shortcut: String,
+    pub always_on_top: bool,
+    pub targets: String,
+    pub user_agent: String,
+    pub show_system_tray: bool,
+    pub system_tray_icon: String,
+    pub inject: Vec<String>,
+    // 引用的片段库中的片段 id，构建时使用最新版本
+    pub snippets: Vec<String>,
+    pub safe_domain: Vec<String>,
+    // 仅 macOS：透明标题栏
+    pub transparent: bool,
+    // 仅 macOS：强制使用深色模式
+    pub dark_mode: bool,
+    pub disabled_web_shortcuts: bool,
+    pub app_version: String,
+    // 仅 Windows：安装程序的语言，例如 zh-CN
+    pub installer_language: String,
+    // 应用内网页使用的代理，与构建时的代理环境变量无关
+    pub proxy_url: String,
+    pub title: String,
+    // pake 3.0 起支持，不勾选时使用 pake 的默认行为
+    pub hide_on_close: bool,
+    pub incognito: bool,
+    // 原样追加在其他参数之后，用于 GUI 尚未支持的 pake 选项
+    pub extra_args: Vec<String>,
+    pub pake_version: Option<String>,
+}
+
+impl Default for PakeConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            name: String::new(),
+            icon: String::new(),
+            icon_source: String::new(),
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            use_local_file: false,
+            local_source: String::new(),
+            fullscreen: false,
+            hide_title_bar: false,
+            multi_arch: false,
+            debug: false,
+            activation_shortcut: String::new(),
+            always_on_top: false,
+            targets: "all".to_string(),
+            user_agent: String::new(),
+            show_system_tray: false,
+            system_tray_icon: String::new(),
+            inject: Vec::new(),
+            snippets: Vec::new(),
+            safe_domain: Vec::new(),
+            transparent: false,
+            dark_mode: false,
+            disabled_web_shortcuts: false,
+            app_version: String::new(),
+            installer_language: String::new(),
+            proxy_url: String::new(),
+            title: String::new(),
+            hide_on_close: false,
+            incognito: false,
+            extra_args: Vec::new(),
+            pake_version: None,
+        }
+    }
+}
+
+impl PakeConfig {
+    pub fn from_value(config: &Value) -> Result<Self, String> {
+        // 前端可能把未填写的字段传成 null，按默认值处理
+        let mut config = config.clone();
+        if let Some(object) = config.as_object_mut() {
+            object.retain(|_, value| !value.is_null());
+        }
+
+        let mut parsed: PakeConfig =
+            serde_json::from_value(config).map_err(|e| format!("Invalid project config: {}", e))?;
+        parsed.url = parsed.url.trim().to_string();
+        parsed.name = parsed.name.trim().to_string();
+        for value in [
+            &mut parsed.app_version,
+            &mut parsed.installer_language,
+            &mut parsed.proxy_url,
+            &mut parsed.title,
+        ] {
+            *value = value.trim().to_string();
+        }
+        if parsed.icon.trim() == LEGACY_DEFAULT_ICON {
+            parsed.icon.clear();
+        }
+        parsed.inject.retain(|file| !file.trim().is_empty());
+        parsed.extra_args.retain(|arg| !arg.trim().is_empty());
+        let mut seen = std::collections::HashSet::new();
+        parsed.snippets.retain(|id| !id.trim().is_empty() && seen.insert(id.clone()));
+        parsed.safe_domain = safe_domain::normalize_all(&parsed.safe_domain);
+        // 能解析的快捷键统一成规范写法，不能解析的原样保留，由校验报告错误
+        parsed.activation_shortcut = match Accelerator::parse(&parsed.activation_shortcut) {
+            Ok(accelerator) => accelerator.to_string(),
+            Err(_) => parsed.activation_shortcut.trim().to_string(),
+        };
+        Ok(parsed)
+    }
+
+    /// 固定的 pake-cli 版本，空字符串视为未固定
+    pub fn pinned_version(&self) -> Option<&str> {
+        self.pake_version.as_deref().map(str::trim).filter(|v| !v.is_empty())
+    }
+
+    /// 生成 pake 命令行参数，extraArgs 追加在最后
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = self.form_args();
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+
+    /// 由表单中的选项生成的参数，与默认值相同的选项不传
+    pub fn form_args(&self) -> Vec<String> {
+        let mut args = ArgsBuilder::default();
+
+        args.positional(&self.url);
+        args.value("--name", &self.name);
+        args.value("--icon", &self.icon);
+        if self.width != DEFAULT_WIDTH {
+            args.value("--width", &self.width.to_string());
+        }
+        if self.height != DEFAULT_HEIGHT {
+            args.value("--height", &self.height.to_string());
+        }
+        args.flag("--use-local-file", self.use_local_file);
+        args.flag("--fullscreen", self.fullscreen);
+        args.flag("--hide-title-bar", self.hide_title_bar);
+        args.flag("--multi-arch", self.multi_arch);
+        args.flag("--debug", self.debug);
+        args.value("--activation-shortcut", &self.activation_shortcut);
+        args.flag("--always-on-top", self.always_on_top);
+        if self.targets != "all" {
+            args.value("--targets", &self.targets);
+        }
+        args.value("--user-agent", &self.user_agent);
+        args.flag("--show-system-tray", self.show_system_tray);
+        args.value("--system-tray-icon", &self.system_tray_icon);
+        for file in &self.inject {
+            args.value("--inject", file);
+        }
+        for domain in &self.safe_domain {
+            args.value("--safe-domain", domain);
+        }
+        args.flag("--transparent", self.transparent);
+        args.flag("--dark-mode", self.dark_mode);
+        args.flag("--disabled-web-shortcuts", self.disabled_web_shortcuts);
+        args.value("--app-version", &self.app_version);
+        args.value("--installer-language", &self.installer_language);
+        args.value("--proxy-url", &self.proxy_url);
+        args.value("--title", &self.title);
+        args.flag("--hide-on-close", self.hide_on_close);
+        args.flag("--incognito", self.incognito);
+
+        args.into_args()
+    }
+}
+
+/// 参数对应的选项名：--name=value 取 --name；不是选项时返回 None
+pub fn flag_name(arg: &str) -> Option<String> {
+    let name = arg.trim().split('=').next()?;
+    (name.starts_with("--") && name.len() > 2).then(|| name.to_string())
+}
+
+/// 选项对应的配置字段：--app-version 对应 appVersion，--no-x 对应 x
+pub fn field_name(flag: &str) -> String {
+    let name = flag.trim_start_matches('-');
+    let mut field = String::new();
+    let mut upper = false;
+    for c in name.strip_prefix("no-").unwrap_or(name).chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            field.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            field.push(c);
+        }
+    }
+    field
+}
+
+#[derive(Default)]
+struct ArgsBuilder {
+    args: Vec<String>,
+}
+
+impl ArgsBuilder {
+    fn positional(&mut self, value: &str) {
+        self.args.push(value.to_string());
+    }
+
+    fn flag(&mut self, flag: &str, enabled: bool) {
+        if enabled {
+            self.args.push(flag.to_string());
+        }
+    }
+
+    /// 值为空时不传这个选项
+    fn value(&mut self, flag: &str, value: &str) {
+        if !value.is_empty() {
+            self.args.push(flag.to_string());
+            self.args.push(value.to_string());
+        }
+    }
+
+    fn into_args(self) -> Vec<String> {
+        self.args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com";
+
+    // url 之后的参数
+    fn args(update: impl FnOnce(&mut PakeConfig)) -> Vec<String> {
+        let mut config = PakeConfig {
+            url: URL.to_string(),
+            ..PakeConfig::default()
+        };
+        update(&mut config);
+        let args = config.to_args();
+        assert_eq!(args[0], URL);
+        args[1..].to_vec()
+    }
+
+    #[test]
+    fn defaults_are_not_passed() {
+        assert!(args(|_| {}).is_empty());
+    }
+
+    #[test]
+    fn boolean_options() {
+        assert_eq!(args(|c| c.transparent = true), ["--transparent"]);
+        assert_eq!(args(|c| c.dark_mode = true), ["--dark-mode"]);
+        assert_eq!(args(|c| c.disabled_web_shortcuts = true), ["--disabled-web-shortcuts"]);
+        assert_eq!(args(|c| c.incognito = true), ["--incognito"]);
+        assert_eq!(args(|c| c.hide_on_close = true), ["--hide-on-close"]);
+    }
+
+    #[test]
+    fn value_options() {
+        assert_eq!(args(|c| c.app_version = "1.2.3".to_string()), ["--app-version", "1.2.3"]);
+        assert_eq!(args(|c| c.installer_language = "zh-CN".to_string()), ["--installer-language", "zh-CN"]);
+        assert_eq!(
+            args(|c| c.proxy_url = "socks5://127.0.0.1:1080".to_string()),
+            ["--proxy-url", "socks5://127.0.0.1:1080"]
+        );
+        assert_eq!(args(|c| c.title = "My App".to_string()), ["--title", "My App"]);
+    }
+
+    #[test]
+    fn extra_args_come_last() {
+        let args = args(|c| {
+            c.incognito = true;
+            c.extra_args = vec!["--new-option".to_string()];
+        });
+        assert_eq!(args, ["--incognito", "--new-option"]);
+    }
+
+    #[test]
+    fn from_value_trims_new_fields() {
+        let config = PakeConfig::from_value(&serde_json::json!({
+            "url": URL,
+            "appVersion": " 1.0.0 ",
+            "title": " My App ",
+            "hideOnClose": null,
+        }))
+        .unwrap();
+        assert_eq!(config.app_version, "1.0.0");
+        assert_eq!(config.title, "My App");
+        assert!(!config.hide_on_close);
+    }
+}
@@ -0,0 +1,358 @@
+use crate::environment::{EnvironmentStatus, Status};
+use crate::icon;
+use crate::inject;
+use crate::local_app;
+use crate::pake_capabilities::PakeCapabilities;
+use crate::pake_config::{self, PakeConfig, KNOWN_FLAGS};
+use crate::project_env::ProjectEnv;
+use crate::safe_domain;
+use crate::search_path::SearchPath;
+use crate::shortcut::{self, Accelerator};
+use crate::snippets::{self, SnippetLibrary};
+use crate::toolchain::ManagedToolchain;
+use crate::tools::Platform;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightIssue {
+    pub severity: Severity,
+    // 对应的配置字段，例如 "icon"；工具链问题为空
+    pub field: Option<String>,
+    // 机器可读的问题类型，例如 "fileNotFound"
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &PreflightIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    fn error(&mut self, field: Option<&str>, code: &str, message: impl Into<String>) {
+        self.push(Severity::Error, field, code, message);
+    }
+
+    fn warning(&mut self, field: Option<&str>, code: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, field, code, message);
+    }
+
+    fn push(&mut self, severity: Severity, field: Option<&str>, code: &str, message: impl Into<String>) {
+        self.issues.push(PreflightIssue {
+            severity,
+            field: field.map(str::to_string),
+            code: code.to_string(),
+            message: message.into(),
+        });
+    }
+}
+
+// 构建 Tauri 应用必需的工具，缺失时 pake 要编译很久之后才会报错
+const REQUIRED_TOOLS: &[&str] = &["nodejs", "rust", "cargo", "visualStudio", "linuxDeps"];
+
+/// 构建前检查配置和工具链，把几分钟后才会出现的失败提前报告出来
+pub struct Preflight<'a> {
+    config: &'a PakeConfig,
+    project_env: &'a ProjectEnv,
+    project_dir: &'a Path,
+    environment: &'a HashMap<String, EnvironmentStatus>,
+    // 所用 pake-cli 版本支持的选项，未知时不检查
+    capabilities: Option<&'a PakeCapabilities>,
+}
+
+impl<'a> Preflight<'a> {
+    pub fn new(
+        config: &'a PakeConfig,
+        project_env: &'a ProjectEnv,
+        project_dir: &'a Path,
+        environment: &'a HashMap<String, EnvironmentStatus>,
+    ) -> Self {
+        Self {
+            config,
+            project_env,
+            project_dir,
+            environment,
+            capabilities: None,
+        }
+    }
+
+    pub fn with_capabilities(mut self, capabilities: Option<&'a PakeCapabilities>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn run(&self) -> PreflightReport {
+        let mut report = PreflightReport::default();
+
+        // 名称、URL 格式和窗口尺寸与保存项目时使用同一套规则
+        let current = Platform::current();
+        let platform_errors = validation::validate_platforms(self.config, &Platform::ALL);
+        let current_errors = platform_errors.get(current).cloned().unwrap_or_default();
+        for (field, message) in current_errors.iter() {
+            report.error(Some(field), "invalid", message);
+        }
+        // 其他平台上才会出现的问题只给出警告，例如 Linux 上不允许的大写和空格
+        for (platform, errors) in platform_errors.iter().filter(|(platform, _)| *platform != current) {
+            for (field, message) in errors.iter().filter(|error| !current_errors.iter().any(|e| e == *error)) {
+                report.warning(Some(field), "invalidOnPlatform", format!("{}: {}", platform.label(), message));
+            }
+        }
+        self.check_url(&mut report);
+        self.check_icon(&mut report);
+        self.check_tray_icon(&mut report);
+        self.check_inject(&mut report);
+        self.check_shortcut(&mut report);
+        self.check_safe_domain(&mut report);
+        self.check_platform_options(&mut report);
+        self.check_extra_args(&mut report);
+        self.check_capabilities(&mut report);
+        self.check_snippets(&mut report);
+        if let Err(e) = self.project_env.validate() {
+            report.error(Some("env"), "invalidEnv", e);
+        }
+        self.check_toolchain(&mut report);
+
+        report
+    }
+
+    /// 相对路径按 pake 的工作目录（项目目录）解析
+    fn resolve(&self, path: &str) -> PathBuf {
+        local_app::resolve_path(path, self.project_dir)
+    }
+
+    fn check_url(&self, report: &mut PreflightReport) {
+        let url = &self.config.url;
+        if url.is_empty() {
+            return;
+        }
+
+        if url.starts_with("http://") || url.starts_with("https://") {
+            if self.config.use_local_file {
+                report.warning(Some("useLocalFile"), "localFileWithRemoteUrl", "useLocalFile is set but the URL is remote");
+            }
+            return;
+        }
+
+        // 不是 http(s) 地址时按本地文件或目录处理
+        if let Err(e) = local_app::check(self.config, self.project_dir) {
+            report.error(Some("url"), "fileNotFound", e);
+        } else if !self.config.use_local_file && self.resolve(url).is_file() {
+            report.warning(
+                Some("useLocalFile"),
+                "localFileWithoutFlag",
+                "useLocalFile is off, resources referenced by the HTML file will not be bundled",
+            );
+        }
+    }
+
+    fn check_icon(&self, report: &mut PreflightReport) {
+        // 保存项目时会把图标转换成当前平台需要的格式，这里只检查源图片
+        match icon::check(&self.config.icon, self.project_dir) {
+            Ok(warnings) => {
+                for warning in warnings {
+                    report.warning(Some("icon"), "iconSize", warning);
+                }
+            }
+            Err(e) => report.error(Some("icon"), "invalidIcon", e),
+        }
+    }
+
+    fn check_tray_icon(&self, report: &mut PreflightReport) {
+        let tray_icon = &self.config.system_tray_icon;
+        if tray_icon.trim().is_empty() || tray_icon.starts_with("http://") || tray_icon.starts_with("https://") {
+            return;
+        }
+
+        let path = self.resolve(tray_icon);
+        if !path.is_file() {
+            report.error(Some("systemTrayIcon"), "fileNotFound", format!("Icon not found: {}", path.display()));
+            return;
+        }
+
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !["ico", "icns", "png"].contains(&extension.as_str()) {
+            report.error(
+                Some("systemTrayIcon"),
+                "unsupportedIconFormat",
+                format!("Unsupported icon format .{}, use .icns, .ico or .png", extension),
+            );
+        }
+    }
+
+    fn check_inject(&self, report: &mut PreflightReport) {
+        for file in &self.config.inject {
+            let path = self.resolve(file);
+            if let Err(e) = inject::check_type(&path) {
+                report.error(Some("inject"), "unsupportedInjectFile", e);
+            }
+            if let Err(e) = std::fs::File::open(&path) {
+                report.error(
+                    Some("inject"),
+                    "fileNotReadable",
+                    format!("Cannot read inject file {}: {}", path.display(), e),
+                );
+            }
+        }
+    }
+
+    fn check_snippets(&self, report: &mut PreflightReport) {
+        if self.config.snippets.is_empty() {
+            return;
+        }
+        let checked = SnippetLibrary::new()
+            .and_then(|library| library.load())
+            .map_err(|e| format!("Failed to load the snippet library: {}", e))
+            .and_then(|library| snippets::check(self.config, &library));
+        if let Err(e) = checked {
+            report.error(Some("snippets"), "missingSnippet", e);
+        }
+    }
+
+    fn check_shortcut(&self, report: &mut PreflightReport) {
+        // 格式错误已经由 validation 报告
+        let Ok(accelerator) = Accelerator::parse(&self.config.activation_shortcut) else { return };
+        let platform = Platform::current();
+
+        if accelerator.captures_typing() {
+            report.warning(
+                Some("activationShortcut"),
+                "shortcutCapturesTyping",
+                format!("{} has no Cmd, Ctrl or Alt, other apps will not receive this key", accelerator.label(platform)),
+            );
+        }
+
+        let project_id = self.project_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        match shortcut::conflicts(&accelerator, &project_id, platform) {
+            Ok(conflicts) => {
+                for conflict in conflicts {
+                    report.warning(
+                        Some("activationShortcut"),
+                        "shortcutConflict",
+                        format!(
+                            "{} is also used by {}, only the app started first can register it",
+                            accelerator.label(platform),
+                            conflict.project_name
+                        ),
+                    );
+                }
+            }
+            Err(e) => report.warning(Some("activationShortcut"), "shortcutConflict", format!("Failed to check other projects: {}", e)),
+        }
+    }
+
+    fn check_safe_domain(&self, report: &mut PreflightReport) {
+        // 设置了 safeDomain 时只有列表中的域名在应用内打开，漏掉主域名会让站内跳转跑到浏览器
+        if let Some(host) = safe_domain::missing_main_host(&self.config.url, &self.config.safe_domain) {
+            report.warning(
+                Some("safeDomain"),
+                "mainHostNotSafe",
+                format!("The app URL's host {} is not in safeDomain", host),
+            );
+        }
+    }
+
+    /// 只在部分平台上生效的选项，在其他平台上构建时 pake 会忽略
+    fn check_platform_options(&self, report: &mut PreflightReport) {
+        let platform = Platform::current();
+        let options = [
+            ("transparent", self.config.transparent, Platform::Macos),
+            ("darkMode", self.config.dark_mode, Platform::Macos),
+            ("installerLanguage", !self.config.installer_language.is_empty(), Platform::Windows),
+        ];
+        for (field, enabled, supported) in options {
+            if enabled && platform != supported {
+                report.warning(
+                    Some(field),
+                    "unsupportedOnPlatform",
+                    format!("{} only takes effect when building on {} and will be ignored", field, supported.label()),
+                );
+            }
+        }
+    }
+
+    fn check_extra_args(&self, report: &mut PreflightReport) {
+        // 与表单中已经设置的选项冲突时由 validation 报告错误，这里提醒改用表单中的选项
+        let form_flags: Vec<String> = self.config.form_args().iter().filter_map(|arg| pake_config::flag_name(arg)).collect();
+        for arg in &self.config.extra_args {
+            let Some(flag) = pake_config::flag_name(arg) else { continue };
+            if KNOWN_FLAGS.contains(&flag.as_str()) && !form_flags.contains(&flag) {
+                report.warning(
+                    Some("extraArgs"),
+                    "extraArgHasOption",
+                    format!("{} is available in the project options, set it there instead", flag),
+                );
+            }
+        }
+    }
+
+    /// 所装的 pake-cli 版本不支持的选项：确定不支持时报错，只能参照内置选项表时给出警告
+    fn check_capabilities(&self, report: &mut PreflightReport) {
+        let Some(capabilities) = self.capabilities else { return };
+        let args = self.config.to_args();
+        let extra_flags: Vec<String> = self.config.extra_args.iter().filter_map(|arg| pake_config::flag_name(arg)).collect();
+
+        for option in capabilities.unsupported(&args) {
+            let flag = pake_config::flag_name(option).unwrap_or_else(|| option.to_string());
+            let field = if extra_flags.contains(&flag) { "extraArgs".to_string() } else { pake_config::field_name(&flag) };
+            if capabilities.authoritative {
+                report.error(
+                    Some(&field),
+                    "unsupportedOption",
+                    format!("pake-cli {} does not support {}", capabilities.version, option),
+                );
+            } else {
+                report.warning(
+                    Some(&field),
+                    "unsupportedOption",
+                    format!("{} may not be supported by pake-cli {}", option, capabilities.version),
+                );
+            }
+        }
+    }
+
+    fn check_toolchain(&self, report: &mut PreflightReport) {
+        let search_path = SearchPath::load();
+        let pake = ManagedToolchain::new().and_then(|toolchain| toolchain.resolve_pake(self.config.pinned_version(), &search_path));
+        if let Err(e) = pake {
+            report.error(None, "missingPake", e.to_string());
+        }
+
+        let mut required: Vec<String> = REQUIRED_TOOLS.iter().map(|id| id.to_string()).collect();
+        if self.config.multi_arch && Platform::current() == Platform::Macos {
+            required.push("rustTarget:aarch64-apple-darwin".to_string());
+            required.push("rustTarget:x86_64-apple-darwin".to_string());
+        }
+
+        for id in required {
+            let Some(status) = self.environment.get(&id) else { continue };
+            let message = status.diagnosis.clone().unwrap_or_else(|| format!("{} is not available", id));
+            match status.status {
+                Status::Ok => {}
+                Status::Warning => report.warning(None, "toolchainWarning", message),
+                Status::Error => report.error(None, "missingToolchain", message),
+            }
+        }
+    }
+}
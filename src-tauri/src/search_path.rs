@@ -0,0 +1,122 @@
+use crate::settings::SettingsManager;
+use crate::shell_env;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// 从桌面启动时 PATH 通常只有系统目录，这些是常见工具的默认安装位置
+fn common_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(home) = dirs::home_dir() {
+        for dir in [".bun/bin", ".cargo/bin", ".volta/bin", ".local/bin", ".npm-global/bin", "Library/pnpm"] {
+            dirs.push(home.join(dir));
+        }
+        if cfg!(target_os = "windows") {
+            dirs.push(home.join("AppData").join("Roaming").join("npm"));
+            dirs.push(home.join("AppData").join("Local").join("pnpm"));
+        }
+    }
+
+    if !cfg!(target_os = "windows") {
+        for dir in ["/opt/homebrew/bin", "/usr/local/bin"] {
+            dirs.push(PathBuf::from(dir));
+        }
+    }
+
+    dirs
+}
+
+/// 设置页中展示的路径配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathSettings {
+    pub binary_paths: HashMap<String, String>,
+    pub extra_path: Vec<String>,
+    // 自动发现且实际存在的目录，只读
+    #[serde(default)]
+    pub discovered: Vec<String>,
+}
+
+/// 查找可执行文件时使用的路径：用户指定的可执行文件 > 额外 PATH > 自动发现的目录 > 登录 shell / 进程 PATH
+pub struct SearchPath {
+    binary_paths: HashMap<String, String>,
+    dirs: Vec<PathBuf>,
+}
+
+impl SearchPath {
+    pub fn load() -> Self {
+        let settings = SettingsManager::new()
+            .and_then(|manager| manager.load())
+            .unwrap_or_default();
+
+        let mut dirs: Vec<PathBuf> = settings.extra_path.iter().map(PathBuf::from).collect();
+        dirs.extend(common_dirs().into_iter().filter(|dir| dir.is_dir()));
+        // 登录 shell 的 PATH 通常包含进程 PATH，两者都保留以防 shell 配置覆盖了 PATH
+        if let Some(path) = shell_env::captured().and_then(|env| env.path()) {
+            dirs.extend(std::env::split_paths(path));
+        }
+        if let Some(path) = std::env::var_os("PATH") {
+            dirs.extend(std::env::split_paths(&path));
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        dirs.retain(|dir| !dir.as_os_str().is_empty() && seen.insert(dir.clone()));
+
+        Self {
+            binary_paths: settings.binary_paths,
+            dirs,
+        }
+    }
+
+    /// 用户为某个可执行文件（例如 "pake"）指定的路径
+    pub fn override_for(&self, binary: &str) -> Option<&str> {
+        self.binary_paths
+            .get(binary)
+            .map(|path| path.trim())
+            .filter(|path| !path.is_empty())
+    }
+
+    /// 指定了路径时直接使用（即使不存在，也交给调用方报告），否则在搜索路径中查找
+    pub fn which(&self, binary: &str) -> Option<PathBuf> {
+        if let Some(path) = self.override_for(binary) {
+            return Some(PathBuf::from(path));
+        }
+        which::which_in(binary, Some(self.path_env()), std::env::current_dir().ok()?).ok()
+    }
+
+    /// 传给子进程的 PATH，指定了路径的可执行文件所在目录也会加入，
+    /// 这样 pake 调用的 node、cargo 等能找到同一套工具
+    pub fn path_env(&self) -> OsString {
+        let override_dirs = self
+            .binary_paths
+            .values()
+            .filter_map(|path| Path::new(path.trim()).parent())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf);
+
+        let mut seen = std::collections::HashSet::new();
+        let dirs: Vec<PathBuf> = override_dirs
+            .chain(self.dirs.iter().cloned())
+            .filter(|dir| seen.insert(dir.clone()))
+            .collect();
+        std::env::join_paths(dirs).unwrap_or_default()
+    }
+
+    pub fn settings() -> PathSettings {
+        let settings = SettingsManager::new()
+            .and_then(|manager| manager.load())
+            .unwrap_or_default();
+
+        PathSettings {
+            binary_paths: settings.binary_paths,
+            extra_path: settings.extra_path,
+            discovered: common_dirs()
+                .into_iter()
+                .filter(|dir| dir.is_dir())
+                .map(|dir| dir.to_string_lossy().to_string())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::tools::ToolSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    // 用户在设置页中添加的自定义工具，与内置工具一起参与环境检查
+    #[serde(default)]
+    pub custom_tools: Vec<ToolSpec>,
+    // 安装 pake-cli 缓存版本时使用的 npm 镜像，例如 https://registry.npmmirror.com
+    #[serde(default)]
+    pub pake_registry: Option<String>,
+    // 按可执行文件名指定的路径，例如 {"pake": "/Users/me/.bun/bin/pake"}
+    #[serde(default)]
+    pub binary_paths: HashMap<String, String>,
+    // 追加到 PATH 前面的目录，检查环境和构建时都会使用
+    #[serde(default)]
+    pub extra_path: Vec<String>,
+}
+
+pub struct SettingsManager {
+    settings_path: PathBuf,
+}
+
+impl SettingsManager {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut settings_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+        settings_dir.push(".pake-gui");
+
+        if !settings_dir.exists() {
+            fs::create_dir_all(&settings_dir)?;
+        }
+
+        Ok(Self {
+            settings_path: settings_dir.join("settings.json"),
+        })
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.settings_path
+    }
+
+    pub fn load(&self) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        if !self.settings_path.exists() {
+            return Ok(AppSettings::default());
+        }
+
+        let content = fs::read_to_string(&self.settings_path)?;
+        let settings: AppSettings = serde_json::from_str(&content)?;
+        Ok(settings)
+    }
+
+    pub fn save(&self, settings: &AppSettings) -> Result<(), Box<dyn std::error::Error>> {
+        let content = serde_json::to_string_pretty(settings)?;
+        fs::write(&self.settings_path, content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command as AsyncCommand;
+use tokio::sync::OnceCell;
+
+// 交互式登录 shell 可能加载很重的配置（nvm、conda 等），超时后退回到进程自身的环境
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 用于从 shell 配置打印的欢迎信息等输出中截取 env 的结果
+const MARKER: &str = "__PAKE_GUI_ENV__";
+
+// 这些变量只对那一次 shell 会话有意义，不传给子进程
+const IGNORED_VARS: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL", "PS1", "PS2", "TERM", "TERM_PROGRAM", "COLUMNS", "LINES"];
+
+static LOGIN_ENV: OnceCell<ShellEnvironment> = OnceCell::const_new();
+
+/// 启动时从用户的登录 shell 中读取的环境变量
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellEnvironment {
+    // 使用的 shell，例如 /bin/zsh；Windows 上为空
+    pub shell: Option<String>,
+    pub vars: HashMap<String, String>,
+    // 读取失败的原因，失败时 vars 为空
+    pub error: Option<String>,
+}
+
+impl ShellEnvironment {
+    /// 登录 shell 中的 PATH，读取失败时为空
+    pub fn path(&self) -> Option<&str> {
+        self.vars.get("PATH").map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EnvSource {
+    Process,
+    LoginShell,
+    // PATH 由设置、自动发现的目录和 shell PATH 合并而来
+    SearchPath,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+    pub source: EnvSource,
+}
+
+/// 检查环境和构建时子进程实际看到的环境变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveEnvironment {
+    pub shell: Option<String>,
+    pub error: Option<String>,
+    pub vars: Vec<EnvVar>,
+}
+
+pub fn effective(path_env: &OsStr) -> EffectiveEnvironment {
+    let mut vars: HashMap<String, EnvVar> = std::env::vars()
+        .map(|(name, value)| {
+            let var = EnvVar {
+                name: name.clone(),
+                value,
+                source: EnvSource::Process,
+            };
+            (name, var)
+        })
+        .collect();
+
+    for (name, value) in imported_vars() {
+        // 与进程环境相同的值仍然算作进程环境
+        if vars.get(&name).is_some_and(|var| var.value == value) {
+            continue;
+        }
+        let var = EnvVar {
+            name: name.clone(),
+            value,
+            source: EnvSource::LoginShell,
+        };
+        vars.insert(name, var);
+    }
+
+    vars.insert(
+        "PATH".to_string(),
+        EnvVar {
+            name: "PATH".to_string(),
+            value: path_env.to_string_lossy().to_string(),
+            source: EnvSource::SearchPath,
+        },
+    );
+
+    let mut vars: Vec<EnvVar> = vars.into_values().collect();
+    vars.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let login = captured();
+    EffectiveEnvironment {
+        shell: login.and_then(|env| env.shell.clone()),
+        error: login.and_then(|env| env.error.clone()),
+        vars,
+    }
+}
+
+/// 在后台开始读取，应用启动时调用一次；失败原因记录在 ShellEnvironment.error 中，
+/// 由 get_effective_environment 返回给环境检查页面
+pub fn init() {
+    tauri::async_runtime::spawn(async {
+        load().await;
+    });
+}
+
+/// 等待读取完成，多次调用只会读取一次
+pub async fn load() -> &'static ShellEnvironment {
+    LOGIN_ENV.get_or_init(capture).await
+}
+
+/// 已经读取完成的环境，尚未完成时为空
+pub fn captured() -> Option<&'static ShellEnvironment> {
+    LOGIN_ENV.get()
+}
+
+/// 需要传给子进程的变量，不包括 PATH（PATH 由 SearchPath 统一计算）
+pub fn imported_vars() -> HashMap<String, String> {
+    captured()
+        .map(|env| {
+            env.vars
+                .iter()
+                .filter(|(name, _)| name.as_str() != "PATH" && !IGNORED_VARS.contains(&name.as_str()))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn capture() -> ShellEnvironment {
+    if cfg!(target_os = "windows") {
+        // Windows 上 GUI 进程本身就继承了用户环境变量
+        return ShellEnvironment::default();
+    }
+
+    let shell = std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string());
+    let script = format!("printf '{0}'; env -0; printf '{0}'", MARKER);
+
+    let child = AsyncCommand::new(&shell)
+        .args(["-i", "-l", "-c", &script])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let result = match tokio::time::timeout(CAPTURE_TIMEOUT, child).await {
+        Ok(Ok(output)) if output.status.success() => parse_env(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| "Could not find environment in shell output".to_string()),
+        Ok(Ok(output)) => Err(format!(
+            "`{} -i -l -c env` exited with code {}",
+            shell,
+            output.status.code().map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string())
+        )),
+        Ok(Err(e)) => Err(format!("Failed to start {}: {}", shell, e)),
+        Err(_) => Err(format!("{} did not finish within {} seconds", shell, CAPTURE_TIMEOUT.as_secs())),
+    };
+
+    match result {
+        Ok(vars) => ShellEnvironment {
+            shell: Some(shell),
+            vars,
+            error: None,
+        },
+        Err(error) => ShellEnvironment {
+            shell: Some(shell),
+            vars: HashMap::new(),
+            error: Some(error),
+        },
+    }
+}
+
+/// 解析两个标记之间由 NUL 分隔的 NAME=value 列表，值中可以包含换行
+fn parse_env(output: &str) -> Option<HashMap<String, String>> {
+    let start = output.find(MARKER)? + MARKER.len();
+    let end = start + output[start..].find(MARKER)?;
+
+    let vars = output[start..end]
+        .split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    Some(vars)
+}
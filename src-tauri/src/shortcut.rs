@@ -0,0 +1,329 @@
+use crate::project::ProjectManager;
+use crate::tools::Platform;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+
+/// 快捷键的修饰键，顺序即规范化后的书写顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Modifier {
+    // macOS 上是 Cmd，其他平台上是 Ctrl
+    CmdOrCtrl,
+    Super,
+    Ctrl,
+    Alt,
+    Shift,
+}
+
+impl Modifier {
+    // 与 Tauri global-shortcut 接受的写法一致，不区分大小写
+    fn parse(token: &str) -> Option<Self> {
+        match token.to_ascii_uppercase().as_str() {
+            "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => Some(Modifier::CmdOrCtrl),
+            "COMMAND" | "CMD" | "SUPER" => Some(Modifier::Super),
+            "CONTROL" | "CTRL" => Some(Modifier::Ctrl),
+            "OPTION" | "ALT" => Some(Modifier::Alt),
+            "SHIFT" => Some(Modifier::Shift),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Modifier::CmdOrCtrl => "CmdOrCtrl",
+            Modifier::Super => "Super",
+            Modifier::Ctrl => "Ctrl",
+            Modifier::Alt => "Alt",
+            Modifier::Shift => "Shift",
+        }
+    }
+
+    /// 在指定平台上的显示名称
+    fn label(self, platform: Platform) -> &'static str {
+        match (self, platform) {
+            (Modifier::Super, Platform::Macos) => "Cmd",
+            (Modifier::Alt, Platform::Macos) => "Option",
+            (Modifier::Super, Platform::Windows) => "Win",
+            _ => self.name(),
+        }
+    }
+}
+
+// 有多种写法的按键，第一个是规范写法
+const NAMED_KEYS: &[&[&str]] = &[
+    &["Backquote", "`"],
+    &["Backslash", "\\"],
+    &["BracketLeft", "["],
+    &["BracketRight", "]"],
+    &["Comma", ","],
+    &["Equal", "="],
+    &["Minus", "-"],
+    &["Period", "."],
+    &["Quote", "'"],
+    &["Semicolon", ";"],
+    &["Slash", "/"],
+    &["Backspace"],
+    &["CapsLock"],
+    &["Enter", "Return"],
+    &["Space"],
+    &["Tab"],
+    &["Delete"],
+    &["End"],
+    &["Home"],
+    &["Insert"],
+    &["PageDown"],
+    &["PageUp"],
+    &["PrintScreen"],
+    &["ScrollLock"],
+    &["ArrowDown", "Down"],
+    &["ArrowLeft", "Left"],
+    &["ArrowRight", "Right"],
+    &["ArrowUp", "Up"],
+    &["Escape", "Esc"],
+    &["NumAdd", "NumpadAdd"],
+    &["NumDecimal", "NumpadDecimal"],
+    &["NumDivide", "NumpadDivide"],
+    &["NumEnter", "NumpadEnter"],
+    &["NumEqual", "NumpadEqual"],
+    &["NumMultiply", "NumpadMultiply"],
+    &["NumSubtract", "NumpadSubtract"],
+    &["AudioVolumeDown", "VolumeDown"],
+    &["AudioVolumeUp", "VolumeUp"],
+    &["AudioVolumeMute", "VolumeMute"],
+    &["MediaPlay"],
+    &["MediaPause"],
+    &["MediaPlayPause"],
+    &["MediaStop"],
+    &["MediaTrackNext"],
+    &["MediaTrackPrevious"],
+];
+
+// 会输入字符的按键，不含字母和数字
+const CHARACTER_KEYS: &[&str] = &[
+    "Backquote", "Backslash", "BracketLeft", "BracketRight", "Comma", "Equal", "Minus", "Period", "Quote",
+    "Semicolon", "Slash", "Space",
+];
+
+// F 键的范围
+const MAX_FUNCTION_KEY: u32 = 24;
+
+/// 返回按键的规范写法，不是 Tauri 支持的按键时返回 None
+fn parse_key(token: &str) -> Option<String> {
+    let upper = token.to_ascii_uppercase();
+
+    // 字母和数字，也接受 KeyA、Digit1 的写法
+    let single = upper
+        .strip_prefix("KEY")
+        .filter(|rest| rest.len() == 1)
+        .or_else(|| upper.strip_prefix("DIGIT").filter(|rest| rest.len() == 1))
+        .unwrap_or(&upper);
+    if single.len() == 1 && single.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Some(single.to_string());
+    }
+
+    for prefix in ["NUMPAD", "NUM"] {
+        if let Some(digit) = upper.strip_prefix(prefix).filter(|rest| rest.len() == 1 && rest.chars().all(|c| c.is_ascii_digit())) {
+            return Some(format!("Num{}", digit));
+        }
+    }
+
+    if let Some(number) = upper.strip_prefix('F').and_then(|rest| rest.parse::<u32>().ok()) {
+        return (1..=MAX_FUNCTION_KEY).contains(&number).then(|| format!("F{}", number));
+    }
+
+    NAMED_KEYS
+        .iter()
+        .find(|names| names.iter().any(|name| name.eq_ignore_ascii_case(token)))
+        .map(|names| names[0].to_string())
+}
+
+/// 解析后的快捷键，例如 CmdOrCtrl+Shift+P
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    modifiers: BTreeSet<Modifier>,
+    key: String,
+}
+
+impl Accelerator {
+    /// 按 Tauri global-shortcut 的语法解析：若干修饰键加一个按键，用 + 连接
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err("Shortcut is empty".to_string());
+        }
+
+        let mut modifiers = BTreeSet::new();
+        let mut key = None;
+        for token in value.split('+').map(str::trim) {
+            if token.is_empty() {
+                return Err(format!("Invalid shortcut {:?}, empty part between '+'", value));
+            }
+            if let Some(modifier) = Modifier::parse(token) {
+                if key.is_some() {
+                    return Err(format!("Invalid shortcut {:?}, modifiers must come before the key", value));
+                }
+                if !modifiers.insert(modifier) {
+                    return Err(format!("Invalid shortcut {:?}, {} is repeated", value, modifier.name()));
+                }
+                continue;
+            }
+            let parsed = parse_key(token).ok_or_else(|| format!("Invalid shortcut {:?}, unknown key {:?}", value, token))?;
+            if key.replace(parsed).is_some() {
+                return Err(format!("Invalid shortcut {:?}, only one key besides the modifiers is allowed", value));
+            }
+        }
+
+        let key = key.ok_or_else(|| format!("Invalid shortcut {:?}, expected modifiers and one key, e.g. CmdOrCtrl+Shift+P", value))?;
+        Ok(Self { modifiers, key })
+    }
+
+    /// 把 CmdOrCtrl 换成目标平台上实际注册的修饰键
+    pub fn resolve(&self, platform: Platform) -> Self {
+        let modifiers = self
+            .modifiers
+            .iter()
+            .map(|&modifier| match (modifier, platform) {
+                (Modifier::CmdOrCtrl, Platform::Macos) => Modifier::Super,
+                (Modifier::CmdOrCtrl, _) => Modifier::Ctrl,
+                _ => modifier,
+            })
+            .collect();
+        Self {
+            modifiers,
+            key: self.key.clone(),
+        }
+    }
+
+    /// 在指定平台上的显示形式，例如 macOS 上的 Cmd+Shift+P
+    pub fn label(&self, platform: Platform) -> String {
+        let resolved = self.resolve(platform);
+        resolved
+            .modifiers
+            .iter()
+            .map(|modifier| modifier.label(platform))
+            .chain(std::iter::once(resolved.key.as_str()))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// 没有 Cmd、Ctrl 或 Alt 的字符键注册为全局快捷键后，在其他应用中也无法输入这个字符
+    pub fn captures_typing(&self) -> bool {
+        let printable = self.key.len() == 1 || CHARACTER_KEYS.contains(&self.key.as_str());
+        printable && self.modifiers.iter().all(|&modifier| modifier == Modifier::Shift)
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier.name())?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// 已经使用同一个全局快捷键的其他项目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutConflict {
+    pub project_id: String,
+    pub project_name: String,
+    pub shortcut: String,
+}
+
+/// 在指定平台上与其他项目注册相同全局快捷键的项目；同时运行时后启动的应用无法注册快捷键
+pub fn conflicts(shortcut: &Accelerator, project_id: &str, platform: Platform) -> Result<Vec<ShortcutConflict>, Box<dyn Error>> {
+    let resolved = shortcut.resolve(platform);
+    let manager = ProjectManager::new()?;
+    Ok(manager
+        .list_projects()?
+        .into_iter()
+        .filter(|project| project.id != project_id)
+        .filter_map(|project| {
+            let other = project.config.get("activationShortcut")?.as_str()?;
+            let parsed = Accelerator::parse(other).ok()?;
+            (parsed.resolve(platform) == resolved).then(|| ShortcutConflict {
+                project_id: project.id,
+                project_name: project.name,
+                shortcut: other.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// 检查快捷键后返回给前端的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutCheck {
+    // 保存到配置中的规范写法
+    pub shortcut: String,
+    // 当前平台上的显示形式
+    pub label: String,
+    pub conflicts: Vec<ShortcutConflict>,
+}
+
+pub fn check(value: &str, project_id: &str, platform: Platform) -> Result<ShortcutCheck, Box<dyn Error>> {
+    let accelerator = Accelerator::parse(value)?;
+    Ok(ShortcutCheck {
+        shortcut: accelerator.to_string(),
+        label: accelerator.label(platform),
+        conflicts: conflicts(&accelerator, project_id, platform)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(value: &str) -> String {
+        Accelerator::parse(value).unwrap().to_string()
+    }
+
+    #[test]
+    fn modifiers_are_written_in_canonical_order() {
+        assert_eq!(canonical("shift+ctrl+p"), "Ctrl+Shift+P");
+        assert_eq!(canonical("Alt + Shift + CmdOrCtrl + F5"), "CmdOrCtrl+Alt+Shift+F5");
+        assert_eq!(canonical("Super+Ctrl+Space"), "Super+Ctrl+Space");
+    }
+
+    #[test]
+    fn aliases_use_canonical_names() {
+        assert_eq!(canonical("CommandOrControl+KeyA"), "CmdOrCtrl+A");
+        assert_eq!(canonical("Command+Option+Digit1"), "Super+Alt+1");
+        assert_eq!(canonical("Control+Esc"), "Ctrl+Escape");
+        assert_eq!(canonical("Ctrl+Return"), "Ctrl+Enter");
+        assert_eq!(canonical("Ctrl+Numpad5"), "Ctrl+Num5");
+        assert_eq!(canonical("Ctrl+up"), "Ctrl+ArrowUp");
+        assert_eq!(canonical("Ctrl+`"), "Ctrl+Backquote");
+    }
+
+    #[test]
+    fn rejects_invalid_shortcuts() {
+        for value in ["", "Ctrl+A+B", "Ctrl+Shift", "Shift", "Ctrl++A", "A+Ctrl", "Ctrl+Ctrl+A", "Ctrl+F25", "Ctrl+Foo"] {
+            assert!(Accelerator::parse(value).is_err(), "{:?} should be rejected", value);
+        }
+        assert!(Accelerator::parse("Ctrl+A+B").unwrap_err().contains("only one key"));
+        assert!(Accelerator::parse("Ctrl+Shift").unwrap_err().contains("expected modifiers and one key"));
+    }
+
+    #[test]
+    fn cmd_or_ctrl_resolves_per_platform() {
+        let shortcut = Accelerator::parse("CmdOrCtrl+Shift+P").unwrap();
+        assert_eq!(shortcut.resolve(Platform::Macos), Accelerator::parse("Super+Shift+P").unwrap());
+        assert_eq!(shortcut.resolve(Platform::Windows), Accelerator::parse("Ctrl+Shift+P").unwrap());
+        assert_eq!(shortcut.resolve(Platform::Linux), Accelerator::parse("Ctrl+Shift+P").unwrap());
+
+        assert_eq!(shortcut.label(Platform::Macos), "Cmd+Shift+P");
+        assert_eq!(shortcut.label(Platform::Windows), "Ctrl+Shift+P");
+        assert_eq!(Accelerator::parse("Super+Alt+K").unwrap().label(Platform::Windows), "Win+Alt+K");
+    }
+
+    #[test]
+    fn character_keys_without_modifiers_capture_typing() {
+        assert!(Accelerator::parse("Shift+A").unwrap().captures_typing());
+        assert!(Accelerator::parse("Space").unwrap().captures_typing());
+        assert!(!Accelerator::parse("Alt+A").unwrap().captures_typing());
+        assert!(!Accelerator::parse("F5").unwrap().captures_typing());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::favicon::{self, IconCandidate, SitePage};
+use crate::fetch::Fetcher;
+use crate::tools::Platform;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+
+/// 根据网站的 web app manifest 和页面信息给出的项目配置建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteProbe {
+    // 跟随重定向之后的地址
+    pub url: String,
+    pub title: Option<String>,
+    pub name: Option<String>,
+    pub short_name: Option<String>,
+    // 按目标平台的名称规则转换后可以直接使用的应用名称
+    pub suggested_name: Option<String>,
+    pub theme_color: Option<String>,
+    pub icons: Vec<IconCandidate>,
+    // 网站重定向到其他域名时需要加入 safeDomain 的域名
+    pub redirect_domain: Option<String>,
+}
+
+/// <meta> 标签的 content，name 或 property 与 key 相同
+fn meta_content(html: &str, key: &str) -> Option<String> {
+    favicon::find_tags(html, "meta").into_iter().find_map(|tag| {
+        let mut attributes = favicon::tag_attributes(tag);
+        let matches = [attributes.get("name"), attributes.get("property")]
+            .into_iter()
+            .flatten()
+            .any(|value| value.eq_ignore_ascii_case(key));
+        if !matches {
+            return None;
+        }
+        attributes.remove("content").filter(|content| !content.is_empty())
+    })
+}
+
+fn title(html: &str) -> Option<String> {
+    let re = regex::Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").ok()?;
+    let title = re.captures(html)?.get(1)?.as_str();
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then(|| title.replace("&amp;", "&"))
+}
+
+/// suggested_name 按 platform 的名称规则生成
+pub async fn probe<F: Fetcher>(fetcher: &F, url: &str, platform: Platform) -> Result<SiteProbe, String> {
+    let requested = url::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if requested.scheme() != "http" && requested.scheme() != "https" {
+        return Err("Only http and https URLs can be probed".to_string());
+    }
+
+    let page = SitePage::load(fetcher, url).await?;
+    let title = title(&page.html);
+    let name = page
+        .manifest_str("name")
+        .or_else(|| meta_content(&page.html, "application-name"))
+        .or_else(|| meta_content(&page.html, "og:site_name"));
+    let short_name = page.manifest_str("short_name");
+
+    // 短名称最适合作为应用名称，标题通常带有页面描述，放在最后
+    let suggested_name = [&short_name, &name, &title]
+        .into_iter()
+        .flatten()
+        .find_map(|candidate| validation::suggest_name(candidate, platform));
+
+    let redirect_domain = match (requested.host_str(), page.url.host_str()) {
+        (Some(from), Some(to)) if !from.eq_ignore_ascii_case(to) => Some(to.to_lowercase()),
+        _ => None,
+    };
+
+    Ok(SiteProbe {
+        url: page.url.to_string(),
+        theme_color: page
+            .manifest_str("theme_color")
+            .or_else(|| meta_content(&page.html, "theme-color")),
+        icons: page.icon_candidates(),
+        title,
+        name,
+        short_name,
+        suggested_name,
+        redirect_domain,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::stub::StubFetcher;
+
+    const PAGE: &str = "https://example.com/";
+
+    fn site(head: &str, manifest: Option<&str>) -> StubFetcher {
+        let html = format!(
+            "<html><head><title>Example Dashboard - Home</title>{}{}</head></html>",
+            head,
+            if manifest.is_some() { r#"<link rel="manifest" href="/manifest.json">"# } else { "" }
+        );
+        let fetcher = StubFetcher::default().serve(PAGE, "text/html", html);
+        match manifest {
+            Some(manifest) => fetcher.serve("https://example.com/manifest.json", "application/json", manifest),
+            None => fetcher,
+        }
+    }
+
+    #[tokio::test]
+    async fn manifest_names_take_precedence() {
+        let fetcher = site(
+            r#"<meta name="application-name" content="Meta Name">"#,
+            Some(r#"{"name": "Example Web App", "short_name": "Example"}"#),
+        );
+        let result = probe(&fetcher, PAGE, Platform::Macos).await.unwrap();
+        assert_eq!(result.name.as_deref(), Some("Example Web App"));
+        assert_eq!(result.short_name.as_deref(), Some("Example"));
+        assert_eq!(result.suggested_name.as_deref(), Some("Example"));
+
+        // 没有清单时使用 <meta> 中的名称，建议名称来自 name
+        let fetcher = site(r#"<meta name="application-name" content="Meta Name">"#, None);
+        let result = probe(&fetcher, PAGE, Platform::Macos).await.unwrap();
+        assert_eq!(result.name.as_deref(), Some("Meta Name"));
+        assert_eq!(result.short_name, None);
+        assert_eq!(result.suggested_name.as_deref(), Some("Meta Name"));
+    }
+
+    #[tokio::test]
+    async fn theme_color_falls_back_to_meta_tag() {
+        let head = r##"<meta name="theme-color" content="#112233">"##;
+        let fetcher = site(head, Some(r##"{"theme_color": "#abcdef"}"##));
+        let result = probe(&fetcher, PAGE, Platform::Macos).await.unwrap();
+        assert_eq!(result.theme_color.as_deref(), Some("#abcdef"));
+
+        let fetcher = site(head, Some(r#"{"name": "Example"}"#));
+        let result = probe(&fetcher, PAGE, Platform::Macos).await.unwrap();
+        assert_eq!(result.theme_color.as_deref(), Some("#112233"));
+    }
+
+    #[tokio::test]
+    async fn redirect_domain_is_reported_for_other_hosts() {
+        let fetcher = site("", None)
+            .redirect("https://example.org/", PAGE)
+            .redirect("https://EXAMPLE.com/start", PAGE);
+
+        let result = probe(&fetcher, "https://example.org/", Platform::Macos).await.unwrap();
+        assert_eq!(result.url, PAGE);
+        assert_eq!(result.redirect_domain.as_deref(), Some("example.com"));
+
+        // 只是路径或大小写不同的重定向不需要加入 safeDomain
+        let result = probe(&fetcher, "https://EXAMPLE.com/start", Platform::Macos).await.unwrap();
+        assert_eq!(result.redirect_domain, None);
+    }
+
+    #[tokio::test]
+    async fn suggested_name_follows_platform_rules() {
+        let fetcher = site("", Some(r#"{"short_name": "My Mail_App"}"#));
+        let linux = probe(&fetcher, PAGE, Platform::Linux).await.unwrap();
+        assert_eq!(linux.suggested_name.as_deref(), Some("my-mail-app"));
+
+        let macos = probe(&fetcher, PAGE, Platform::Macos).await.unwrap();
+        assert_eq!(macos.suggested_name.as_deref(), Some("My Mail App"));
+        let windows = probe(&fetcher, PAGE, Platform::Windows).await.unwrap();
+        assert_eq!(windows.suggested_name, macos.suggested_name);
+    }
+}
@@ -0,0 +1,447 @@
+use crate::history::now_millis;
+use crate::inject;
+use crate::pake_config::PakeConfig;
+use crate::project::ProjectManager;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 构建时把项目引用的片段写到项目目录下的这个子目录中，每次构建都会重新生成
+pub const SNIPPETS_DIR: &str = "snippets";
+// snippets 目录中记录上次构建写入了哪些文件，重新生成时只删除这些文件
+const GENERATED_LIST: &str = ".generated";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnippetKind {
+    Css,
+    Js,
+}
+
+impl SnippetKind {
+    fn extension(self) -> &'static str {
+        match self {
+            SnippetKind::Css => "css",
+            SnippetKind::Js => "js",
+        }
+    }
+}
+
+/// 片段库中的 CSS/JS 片段，项目通过 id 引用，构建时使用最新版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: SnippetKind,
+    pub content: String,
+    // 每次修改内容时加一
+    pub version: u32,
+    pub updated_at: u64,
+}
+
+/// 新建或修改片段时前端传入的内容，没有 id 时新建
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetInput {
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: SnippetKind,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetUsage {
+    pub project_id: String,
+    pub project_name: String,
+}
+
+/// 构建时写入项目的片段及其版本
+#[derive(Debug, Clone)]
+pub struct AppliedSnippet {
+    pub id: String,
+    pub version: u32,
+    pub path: PathBuf,
+}
+
+// 第一次打开片段库时提供的常用片段
+fn presets() -> Vec<Snippet> {
+    let preset = |id: &str, name: &str, description: &str, kind, content: &str| Snippet {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        kind,
+        content: content.to_string(),
+        version: 1,
+        updated_at: now_millis(),
+    };
+    vec![
+        preset(
+            "hide-scrollbars",
+            "Hide scrollbars",
+            "隐藏页面滚动条，页面仍可滚动",
+            SnippetKind::Css,
+            "::-webkit-scrollbar {\n  display: none;\n}\n",
+        ),
+        preset(
+            "dark-mode",
+            "Dark mode",
+            "反转页面颜色，图片和视频保持原样",
+            SnippetKind::Css,
+            "html {\n  filter: invert(0.9) hue-rotate(180deg);\n}\n\nimg, video, picture, canvas {\n  filter: invert(1) hue-rotate(180deg);\n}\n",
+        ),
+        preset(
+            "history-shortcuts",
+            "History shortcuts",
+            "CmdOrCtrl+[ 后退，CmdOrCtrl+] 前进",
+            SnippetKind::Js,
+            "document.addEventListener('keydown', (event) => {\n  if (!(event.metaKey || event.ctrlKey)) return;\n  if (event.key === '[') history.back();\n  if (event.key === ']') history.forward();\n});\n",
+        ),
+    ]
+}
+
+/// 由名称生成 id，例如 "Hide banners" -> "hide-banners"
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "snippet".to_string()
+    } else {
+        slug
+    }
+}
+
+/// id 只能由 slug 生成的字符组成，构建时作为文件名使用
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('-')
+        && !id.ends_with('-')
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// 保存在 ~/.pake-gui/snippets.json 中的全局片段库
+pub struct SnippetLibrary {
+    path: PathBuf,
+}
+
+impl SnippetLibrary {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let mut dir = dirs::home_dir().ok_or("Could not find home directory")?;
+        dir.push(".pake-gui");
+
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+        }
+
+        Ok(Self {
+            path: dir.join("snippets.json"),
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<Snippet>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(presets());
+        }
+        let content = fs::read_to_string(&self.path)?;
+        let snippets: Vec<Snippet> = serde_json::from_str(&content)?;
+        if let Some(snippet) = snippets.iter().find(|snippet| !is_valid_id(&snippet.id)) {
+            return Err(format!(
+                "Invalid snippet id {:?} in {}, only lowercase letters, digits and dashes are allowed",
+                snippet.id,
+                self.path.display()
+            )
+            .into());
+        }
+        Ok(snippets)
+    }
+
+    fn save(&self, snippets: &[Snippet]) -> Result<(), Box<dyn Error>> {
+        let content = serde_json::to_string_pretty(snippets)?;
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+
+    /// 新建片段，或修改已有片段；内容变化时版本号加一
+    pub fn upsert(&self, input: SnippetInput) -> Result<Snippet, Box<dyn Error>> {
+        let name = input.name.trim();
+        if name.is_empty() {
+            return Err("Snippet name is required".into());
+        }
+
+        let mut snippets = self.load()?;
+        let snippet = match input.id.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+            Some(id) => {
+                let snippet = snippets
+                    .iter_mut()
+                    .find(|snippet| snippet.id == id)
+                    .ok_or_else(|| format!("Snippet not found: {}", id))?;
+                if snippet.content != input.content || snippet.kind != input.kind {
+                    snippet.version += 1;
+                }
+                snippet.name = name.to_string();
+                snippet.description = input.description.trim().to_string();
+                snippet.kind = input.kind;
+                snippet.content = input.content;
+                snippet.updated_at = now_millis();
+                snippet.clone()
+            }
+            None => {
+                // 名称相同的片段在 id 后加序号
+                let base = slug(name);
+                let mut id = base.clone();
+                let mut index = 1;
+                while snippets.iter().any(|snippet| snippet.id == id) {
+                    index += 1;
+                    id = format!("{}-{}", base, index);
+                }
+                let snippet = Snippet {
+                    id,
+                    name: name.to_string(),
+                    description: input.description.trim().to_string(),
+                    kind: input.kind,
+                    content: input.content,
+                    version: 1,
+                    updated_at: now_millis(),
+                };
+                snippets.push(snippet.clone());
+                snippet
+            }
+        };
+
+        self.save(&snippets)?;
+        Ok(snippet)
+    }
+
+    /// 删除片段，仍有项目引用时拒绝删除
+    pub fn delete(&self, id: &str) -> Result<(), Box<dyn Error>> {
+        let usage = usage(id)?;
+        if !usage.is_empty() {
+            let names: Vec<&str> = usage.iter().map(|u| u.project_name.as_str()).collect();
+            return Err(format!("Snippet {} is used by: {}", id, names.join(", ")).into());
+        }
+
+        let mut snippets = self.load()?;
+        snippets.retain(|snippet| snippet.id != id);
+        self.save(&snippets)
+    }
+}
+
+/// 引用了指定片段的项目
+pub fn usage(id: &str) -> Result<Vec<SnippetUsage>, Box<dyn Error>> {
+    let manager = ProjectManager::new()?;
+    Ok(manager
+        .list_projects()?
+        .into_iter()
+        .filter(|project| {
+            project
+                .config
+                .get("snippets")
+                .and_then(|value| value.as_array())
+                .is_some_and(|ids| ids.iter().any(|value| value.as_str() == Some(id)))
+        })
+        .map(|project| SnippetUsage {
+            project_id: project.id,
+            project_name: project.name,
+        })
+        .collect())
+}
+
+/// 检查项目引用的片段都存在，返回的错误对应 snippets 字段
+pub fn check(config: &PakeConfig, snippets: &[Snippet]) -> Result<(), String> {
+    let missing: Vec<&str> = config
+        .snippets
+        .iter()
+        .filter(|id| !snippets.iter().any(|snippet| &snippet.id == *id))
+        .map(String::as_str)
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Snippets no longer exist in the library: {}", missing.join(", ")))
+    }
+}
+
+/// 删除上次构建写入的片段文件，目录中用户自己放的文件保留
+fn remove_generated(dir: &Path) -> Result<(), Box<dyn Error>> {
+    let list = dir.join(GENERATED_LIST);
+    let Ok(content) = fs::read_to_string(&list) else {
+        return Ok(());
+    };
+    for name in content.lines().map(str::trim) {
+        // 只删除 snippets 目录中的文件
+        if Path::new(name).file_name() != Some(name.as_ref()) {
+            continue;
+        }
+        let path = dir.join(name);
+        if path.is_file() {
+            fs::remove_file(&path)?;
+        }
+    }
+    fs::remove_file(&list)?;
+    // 目录中没有其他文件时一并删除
+    let _ = fs::remove_dir(dir);
+    Ok(())
+}
+
+/// 把项目引用的片段按最新版本写到项目的 snippets 目录，返回写入的文件，构建时作为 --inject 传给 pake
+pub fn materialize(config: &PakeConfig, project_dir: &Path) -> Result<Vec<AppliedSnippet>, Box<dyn Error>> {
+    let library = if config.snippets.is_empty() {
+        Vec::new()
+    } else {
+        SnippetLibrary::new()?.load()?
+    };
+    write_snippets(config, &library, project_dir)
+}
+
+fn write_snippets(config: &PakeConfig, library: &[Snippet], project_dir: &Path) -> Result<Vec<AppliedSnippet>, Box<dyn Error>> {
+    let dir = project_dir.join(SNIPPETS_DIR);
+    remove_generated(&dir)?;
+    if config.snippets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    check(config, library)?;
+    fs::create_dir_all(&dir)?;
+
+    let mut applied = Vec::new();
+    for id in &config.snippets {
+        let Some(snippet) = library.iter().find(|snippet| &snippet.id == id) else { continue };
+        let path = dir.join(format!("{}.{}", snippet.id, snippet.kind.extension()));
+        inject::check_type(&path)?;
+        fs::write(&path, &snippet.content)?;
+        applied.push(AppliedSnippet {
+            id: snippet.id.clone(),
+            version: snippet.version,
+            path,
+        });
+    }
+
+    let generated: Vec<String> = applied
+        .iter()
+        .filter_map(|snippet| snippet.path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect();
+    fs::write(dir.join(GENERATED_LIST), generated.join("\n"))?;
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pake-gui-snippets-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn input(id: Option<&str>, name: &str, kind: SnippetKind, content: &str) -> SnippetInput {
+        SnippetInput {
+            id: id.map(str::to_string),
+            name: name.to_string(),
+            description: String::new(),
+            kind,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn slug_from_name() {
+        assert_eq!(slug("Hide banners"), "hide-banners");
+        assert_eq!(slug("  Dark   Mode!! "), "dark-mode");
+        assert_eq!(slug("--CSS/JS fix--"), "css-js-fix");
+        assert_eq!(slug("隐藏广告"), "snippet");
+        assert!(is_valid_id(&slug("A_b c")));
+        assert!(!is_valid_id("../x"));
+        assert!(!is_valid_id("-x"));
+    }
+
+    #[test]
+    fn upsert_suffixes_ids_and_bumps_version_on_change() {
+        let dir = temp_dir("library");
+        let library = SnippetLibrary {
+            path: dir.join("snippets.json"),
+        };
+
+        let first = library.upsert(input(None, "Hide banners", SnippetKind::Css, "a")).unwrap();
+        let second = library.upsert(input(None, "Hide Banners", SnippetKind::Css, "b")).unwrap();
+        let third = library.upsert(input(None, "hide banners", SnippetKind::Css, "c")).unwrap();
+        let renamed = library.upsert(input(Some(&first.id), "Renamed", SnippetKind::Css, "a")).unwrap();
+        let edited = library.upsert(input(Some(&first.id), "Renamed", SnippetKind::Css, "a2")).unwrap();
+        let retyped = library.upsert(input(Some(&first.id), "Renamed", SnippetKind::Js, "a2")).unwrap();
+        let missing = library.upsert(input(Some("nope"), "Nope", SnippetKind::Css, "")).is_err();
+        let unnamed = library.upsert(input(None, " ", SnippetKind::Css, "")).is_err();
+        let count = library.load().unwrap().len();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!([first.id.as_str(), second.id.as_str(), third.id.as_str()], ["hide-banners", "hide-banners-2", "hide-banners-3"]);
+        assert_eq!(first.version, 1);
+        assert_eq!((renamed.name.as_str(), renamed.version), ("Renamed", 1));
+        assert_eq!(edited.version, 2);
+        assert_eq!(retyped.version, 3);
+        assert!(missing);
+        assert!(unnamed);
+        // 片段库文件不存在时从预置片段开始
+        assert_eq!(count, presets().len() + 3);
+    }
+
+    #[test]
+    fn regenerating_keeps_files_not_listed() {
+        let project_dir = temp_dir("project");
+        let dir = project_dir.join(SNIPPETS_DIR);
+        let library = presets();
+        let mut config = PakeConfig {
+            snippets: vec!["hide-scrollbars".to_string(), "history-shortcuts".to_string()],
+            ..PakeConfig::default()
+        };
+
+        let applied = write_snippets(&config, &library, &project_dir).unwrap();
+        fs::write(dir.join("custom.css"), "body {}").unwrap();
+        fs::write(dir.join("notes.txt"), "keep").unwrap();
+        let written = dir.join("hide-scrollbars.css").is_file() && dir.join("history-shortcuts.js").is_file();
+
+        config.snippets = vec!["dark-mode".to_string()];
+        write_snippets(&config, &library, &project_dir).unwrap();
+        let mut after: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        after.sort();
+
+        config.snippets.clear();
+        write_snippets(&config, &library, &project_dir).unwrap();
+        let kept = dir.join("custom.css").is_file() && dir.join("notes.txt").is_file();
+        let removed = !dir.join("dark-mode.css").exists() && !dir.join(GENERATED_LIST).exists();
+        let _ = fs::remove_dir_all(&project_dir);
+
+        assert_eq!(applied.len(), 2);
+        assert!(written);
+        assert_eq!(after, [".generated", "custom.css", "dark-mode.css", "notes.txt"]);
+        assert!(kept);
+        assert!(removed);
+    }
+
+    #[test]
+    fn missing_snippets_are_reported() {
+        let config = PakeConfig {
+            snippets: vec!["dark-mode".to_string(), "gone".to_string()],
+            ..PakeConfig::default()
+        };
+        assert!(check(&config, &presets()).unwrap_err().contains("gone"));
+    }
+}
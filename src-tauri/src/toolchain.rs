@@ -0,0 +1,265 @@
+use crate::search_path::SearchPath;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PAKE_PACKAGE: &str = "pake-cli";
+pub const PAKE_BINARY: &str = "pake";
+
+// 确切的版本号（3.1.1、3.0.0-beta.1），缓存目录以安装后 package.json 中的版本号命名，会被拼接到缓存路径中
+const VERSION_PATTERN: &str = r"^\d+\.\d+\.\d+(-[0-9A-Za-z.]+)?$";
+// 安装时还可以使用 npm dist-tag（latest、beta），安装后按实际版本号缓存
+const DIST_TAG_PATTERN: &str = r"^[A-Za-z][0-9A-Za-z-]*$";
+
+fn matches(pattern: &str, value: &str) -> bool {
+    regex::Regex::new(pattern).map(|re| re.is_match(value)).unwrap_or(false)
+}
+
+/// 检查固定或缓存的版本号，拒绝 dist-tag 和 ../x 这类会跳出缓存目录的值
+pub fn validate_version(version: &str) -> Result<(), String> {
+    if matches(VERSION_PATTERN, version) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid pake-cli version {:?}, use an exact version such as 3.1.1; tags such as latest can only be used when installing",
+            version
+        ))
+    }
+}
+
+/// 检查要安装的版本号或 dist-tag
+pub fn validate_install_version(version: &str) -> Result<(), String> {
+    if matches(VERSION_PATTERN, version) || matches(DIST_TAG_PATTERN, version) {
+        Ok(())
+    } else {
+        Err(format!("Invalid pake-cli version {:?}, use a version such as 3.1.1 or a tag such as latest", version))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedVersion {
+    pub version: String,
+    pub path: String,
+}
+
+/// 由 GUI 管理的工具安装目录（~/.pake-gui/.toolchain），不污染全局环境
+pub struct ManagedToolchain {
+    root: PathBuf,
+}
+
+impl ManagedToolchain {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut root = dirs::home_dir().ok_or("Could not find home directory")?;
+        root.push(".pake-gui");
+        root.push(".toolchain");
+        Ok(Self { root })
+    }
+
+    /// 某个 npm 包的安装前缀，例如 ~/.pake-gui/.toolchain/pake-cli
+    pub fn package_dir(&self, package: &str) -> PathBuf {
+        self.root.join(package)
+    }
+
+    pub fn ensure_package_dir(&self, package: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dir = self.package_dir(package);
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+        }
+        Ok(dir)
+    }
+
+    /// 包管理器在本地安装后生成的可执行文件位置
+    pub fn binary_path(&self, package: &str, binary: &str) -> PathBuf {
+        bin_path(&self.package_dir(package), binary)
+    }
+
+    pub fn find_binary(&self, package: &str, binary: &str) -> Option<PathBuf> {
+        let path = self.binary_path(package, binary);
+        path.exists().then_some(path)
+    }
+
+    /// 按版本缓存的安装目录，例如 ~/.pake-gui/.toolchain/pake-cli/versions/3.1.1
+    pub fn versions_dir(&self, package: &str) -> PathBuf {
+        self.package_dir(package).join("versions")
+    }
+
+    pub fn version_dir(&self, package: &str, version: &str) -> PathBuf {
+        self.versions_dir(package).join(version)
+    }
+
+    /// 新版本先安装到临时目录，确认实际版本号后再移动到 versions/<version>
+    pub fn create_staging_dir(&self, package: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis();
+        let dir = self.versions_dir(package).join(format!(".staging-{}", timestamp));
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// 把临时目录提升为正式的版本目录，已存在的同版本会被替换
+    pub fn promote_staging_dir(
+        &self,
+        package: &str,
+        binary: &str,
+        staging: &Path,
+    ) -> Result<ManagedVersion, Box<dyn std::error::Error>> {
+        let version = installed_package_version(staging, package)
+            .ok_or_else(|| format!("{} was not installed into {}", package, staging.display()))?;
+
+        let target = self.version_dir(package, &version);
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(staging, &target)?;
+
+        Ok(ManagedVersion {
+            path: bin_path(&target, binary).to_string_lossy().to_string(),
+            version,
+        })
+    }
+
+    pub fn versioned_binary(&self, package: &str, binary: &str, version: &str) -> Option<PathBuf> {
+        validate_version(version).ok()?;
+        let path = bin_path(&self.version_dir(package, version), binary);
+        path.exists().then_some(path)
+    }
+
+    /// 构建时使用的 pake：项目固定了版本时只使用缓存中的该版本，
+    /// 否则依次使用设置中指定的 / PATH 中的 pake 和 GUI 管理目录中的 pake
+    pub fn resolve_pake(
+        &self,
+        pinned_version: Option<&str>,
+        search_path: &SearchPath,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        match pinned_version.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(version) => {
+                validate_version(version)?;
+                self.versioned_binary(PAKE_PACKAGE, PAKE_BINARY, version).ok_or_else(|| {
+                    format!(
+                        "pake-cli {} is not installed in the managed toolchain cache. Install it from the environment page first",
+                        version
+                    )
+                    .into()
+                })
+            }
+            None => search_path
+                .which(PAKE_BINARY)
+                .or_else(|| self.find_binary(PAKE_PACKAGE, PAKE_BINARY))
+                .ok_or_else(|| "pake was not found on PATH. Install pake-cli from the environment page first".into()),
+        }
+    }
+
+    pub fn list_versions(&self, package: &str, binary: &str) -> Vec<ManagedVersion> {
+        let mut versions = Vec::new();
+
+        if let Ok(entries) = fs::read_dir(self.versions_dir(package)) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // 跳过未完成安装留下的临时目录
+                if name.starts_with('.') {
+                    continue;
+                }
+                let path = bin_path(&entry.path(), binary);
+                if path.exists() {
+                    versions.push(ManagedVersion {
+                        version: name,
+                        path: path.to_string_lossy().to_string(),
+                    });
+                }
+            }
+        }
+
+        versions.sort_by(|a, b| {
+            crate::tools::compare_versions(&b.version, &a.version).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        versions
+    }
+
+    pub fn remove_version(&self, package: &str, version: &str) -> Result<(), Box<dyn std::error::Error>> {
+        validate_version(version)?;
+        let dir = self.version_dir(package, version);
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// npm 在 Windows 上生成的 .cmd 包装脚本实际运行的 JS 文件；不是 .cmd 或无法识别时返回 None。
+/// .cmd 经由 cmd.exe 执行，参数中的 & ( % 等字符会被当作 shell 语法，构建时改用 node 直接运行这个文件
+pub fn cmd_shim_script(shim: &Path) -> Option<PathBuf> {
+    if !shim.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("cmd")) {
+        return None;
+    }
+    let content = fs::read_to_string(shim).ok()?;
+    // 例如 "%_prog%"  "%dp0%\..\pake-cli\dist\cli.js" %*
+    let re = regex::Regex::new(r#""%~?dp0%?\\([^"%]+?\.[cm]?js)""#).ok()?;
+    let relative = re.captures(&content)?.get(1)?.as_str().to_string();
+    let script = relative
+        .split('\\')
+        .filter(|part| !part.is_empty())
+        .fold(shim.parent()?.to_path_buf(), |path, part| path.join(part));
+    script.is_file().then_some(script)
+}
+
+fn bin_path(prefix: &Path, binary: &str) -> PathBuf {
+    let bin_dir = prefix.join("node_modules").join(".bin");
+    if cfg!(target_os = "windows") {
+        bin_dir.join(format!("{}.cmd", binary))
+    } else {
+        bin_dir.join(binary)
+    }
+}
+
+/// 读取安装目录中 node_modules/<package>/package.json 的版本号
+fn installed_package_version(prefix: &Path, package: &str) -> Option<String> {
+    let manifest = prefix.join("node_modules").join(package).join("package.json");
+    let content = fs::read_to_string(manifest).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    value.get("version")?.as_str().map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // npm 为本地安装生成的 node_modules/.bin/pake.cmd
+    const SHIM: &str = "@ECHO off\r\nSETLOCAL\r\nCALL :find_dp0\r\n\
+        IF EXIST \"%dp0%\\node.exe\" (\r\n  SET \"_prog=%dp0%\\node.exe\"\r\n) ELSE (\r\n  SET \"_prog=node\"\r\n)\r\n\
+        endLocal & goto #_undefined_# 2>NUL || title %COMSPEC% & \"%_prog%\"  \"%dp0%\\..\\pake-cli\\dist\\cli.js\" %*\r\n";
+
+    #[test]
+    fn pinned_versions_must_be_exact() {
+        assert!(validate_version("3.1.1").is_ok());
+        assert!(validate_version("3.0.0-beta.1").is_ok());
+        assert!(validate_version("latest").is_err());
+        assert!(validate_version("3.1").is_err());
+        assert!(validate_version("../3.1.1").is_err());
+
+        assert!(validate_install_version("latest").is_ok());
+        assert!(validate_install_version("3.1.1").is_ok());
+        assert!(validate_install_version("../x").is_err());
+        assert!(validate_install_version("3.1.1 && rm").is_err());
+    }
+
+    #[test]
+    fn finds_script_behind_cmd_shim() {
+        let prefix = std::env::temp_dir().join(format!("pake-gui-shim-{}", std::process::id()));
+        let bin = prefix.join("node_modules").join(".bin");
+        let dist = prefix.join("node_modules").join("pake-cli").join("dist");
+        fs::create_dir_all(&bin).unwrap();
+        fs::create_dir_all(&dist).unwrap();
+        fs::write(bin.join("pake.cmd"), SHIM).unwrap();
+        fs::write(bin.join("pake"), "#!/bin/sh\n").unwrap();
+        fs::write(dist.join("cli.js"), "").unwrap();
+
+        let script = cmd_shim_script(&bin.join("pake.cmd"));
+        let not_shim = cmd_shim_script(&bin.join("pake"));
+        let _ = fs::remove_dir_all(&prefix);
+
+        let script = script.expect("script found");
+        assert!(script.ends_with(Path::new("..").join("pake-cli").join("dist").join("cli.js")));
+        assert_eq!(not_shim, None);
+    }
+}
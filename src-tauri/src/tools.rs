@@ -0,0 +1,431 @@
+use crate::settings::SettingsManager;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Windows,
+    Macos,
+    Linux,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 3] = [Platform::Windows, Platform::Macos, Platform::Linux];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Platform::Windows => "Windows",
+            Platform::Macos => "macOS",
+            Platform::Linux => "Linux",
+        }
+    }
+
+    pub fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::Macos
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InstallStrategy {
+    /// 打开工具的下载页面，由用户手动安装
+    Download,
+    /// 通过系统 shell 执行安装脚本，没有对应平台的脚本时退回到下载页面
+    Script {
+        unix: Option<String>,
+        windows: Option<String>,
+    },
+    /// 通过 JS 包管理器全局安装，按顺序使用第一个可用的包管理器
+    GlobalPackage { package: String, managers: Vec<String> },
+    /// 通过 rustup 添加编译目标
+    RustupTarget { target: String },
+    /// 不支持自动安装
+    #[default]
+    None,
+}
+
+impl InstallStrategy {
+    /// 给用户看的安装提示
+    pub fn hint(&self, homepage: Option<&str>) -> String {
+        let download = match homepage {
+            Some(url) => format!("Download it from {}", url),
+            None => "Install it manually".to_string(),
+        };
+
+        match self {
+            InstallStrategy::Download | InstallStrategy::None => download,
+            InstallStrategy::Script { unix, windows } => {
+                let script = if Platform::current() == Platform::Windows { windows } else { unix };
+                match script {
+                    Some(script) => format!("Run: {}", script),
+                    None => download,
+                }
+            }
+            InstallStrategy::GlobalPackage { package, managers } => match managers.first() {
+                Some(manager) => format!("Run: {}", global_install_args(manager, package).join(" ")),
+                None => download,
+            },
+            InstallStrategy::RustupTarget { target } => format!("Run: rustup target add {}", target),
+        }
+    }
+}
+
+/// 各包管理器全局安装一个包所用的命令行
+pub fn global_install_args(manager: &str, package: &str) -> Vec<String> {
+    let args: &[&str] = match manager {
+        "bun" => &["bun", "add", "-g"],
+        "pnpm" => &["pnpm", "add", "-g"],
+        "yarn" => &["yarn", "global", "add"],
+        _ => &["npm", "install", "-g"],
+    };
+    let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    args.push(package.to_string());
+    args
+}
+
+/// 各包管理器把包安装到指定目录（而不是全局）所用的命令行
+pub fn local_install_args(manager: &str, package: &str, dir: &str) -> Vec<String> {
+    let args: &[&str] = match manager {
+        "bun" => &["bun", "add", "--cwd", dir],
+        "pnpm" => &["pnpm", "add", "--dir", dir],
+        "yarn" => &["yarn", "--cwd", dir, "add"],
+        _ => &["npm", "install", "--prefix", dir],
+    };
+    let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    args.push(package.to_string());
+    args
+}
+
+/// 带版本号的包名，例如 pake-cli@3.1.1
+pub fn package_spec(package: &str, version: Option<&str>) -> String {
+    match version {
+        Some(version) if !version.trim().is_empty() => format!("{}@{}", package, version.trim()),
+        _ => package.to_string(),
+    }
+}
+
+fn default_version_args() -> Vec<String> {
+    vec!["--version".to_string()]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSpec {
+    pub id: String,
+    pub name: String,
+    // 按顺序查找的可执行文件名，例如 ["pake"]；为空时只检查 paths
+    #[serde(default)]
+    pub binaries: Vec<String>,
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+    // 从输出中提取版本号的正则，有捕获组时取第一个捕获组
+    #[serde(default)]
+    pub version_regex: Option<String>,
+    // 输出必须匹配的正则，用于 rustup target 这类“是否已安装”的检查
+    #[serde(default)]
+    pub requires: Option<String>,
+    #[serde(default)]
+    pub min_version: Option<String>,
+    // 没有可执行文件的工具（例如 VS Build Tools）通过这些路径是否存在来判断
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub install: InstallStrategy,
+    #[serde(default)]
+    pub homepage: Option<String>,
+    // 为空表示所有平台都需要
+    #[serde(default)]
+    pub platforms: Vec<Platform>,
+    #[serde(default)]
+    pub custom: bool,
+}
+
+impl ToolSpec {
+    pub fn supports_current_platform(&self) -> bool {
+        self.platforms.is_empty() || self.platforms.contains(&Platform::current())
+    }
+
+    pub fn install_hint(&self) -> String {
+        self.install.hint(self.homepage.as_deref())
+    }
+}
+
+const RUSTUP_SCRIPT: &str = "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y";
+
+fn builtin_tools() -> Vec<ToolSpec> {
+    fn spec(id: &str, name: &str, binaries: &[&str], install: InstallStrategy) -> ToolSpec {
+        ToolSpec {
+            id: id.to_string(),
+            name: name.to_string(),
+            binaries: binaries.iter().map(|s| s.to_string()).collect(),
+            version_args: default_version_args(),
+            version_regex: None,
+            requires: None,
+            min_version: None,
+            paths: Vec::new(),
+            install,
+            homepage: None,
+            platforms: Vec::new(),
+            custom: false,
+        }
+    }
+
+    fn rustup_target(target: &str) -> ToolSpec {
+        ToolSpec {
+            version_args: vec!["target".to_string(), "list".to_string(), "--installed".to_string()],
+            version_regex: Some(format!("(?m)^({})$", regex::escape(target))),
+            requires: Some(format!("(?m)^{}$", regex::escape(target))),
+            homepage: Some("https://rustup.rs".to_string()),
+            // 只有 macOS 的多架构构建需要额外的编译目标
+            platforms: vec![Platform::Macos],
+            ..spec(
+                &format!("rustTarget:{}", target),
+                &format!("Rust target {}", target),
+                &["rustup"],
+                InstallStrategy::RustupTarget { target: target.to_string() },
+            )
+        }
+    }
+
+    vec![
+        ToolSpec {
+            min_version: Some("18.0.0".to_string()),
+            homepage: Some("https://nodejs.org".to_string()),
+            ..spec("nodejs", "Node.js", &["node"], InstallStrategy::Download)
+        },
+        ToolSpec {
+            homepage: Some("https://nodejs.org".to_string()),
+            ..spec("npm", "npm", &["npm"], InstallStrategy::Download)
+        },
+        ToolSpec {
+            homepage: Some("https://pnpm.io".to_string()),
+            ..spec(
+                "pnpm",
+                "pnpm",
+                &["pnpm"],
+                InstallStrategy::GlobalPackage {
+                    package: "pnpm".to_string(),
+                    managers: vec!["npm".to_string()],
+                },
+            )
+        },
+        ToolSpec {
+            homepage: Some("https://bun.sh".to_string()),
+            ..spec(
+                "bunjs",
+                "Bun.js",
+                &["bun"],
+                InstallStrategy::Script {
+                    unix: Some("curl -fsSL https://bun.sh/install | bash".to_string()),
+                    windows: Some("irm bun.sh/install.ps1 | iex".to_string()),
+                },
+            )
+        },
+        ToolSpec {
+            // Tauri 2 要求的最低 Rust 版本
+            min_version: Some("1.77.2".to_string()),
+            homepage: Some("https://rustup.rs".to_string()),
+            ..spec(
+                "rust",
+                "Rust",
+                &["rustc"],
+                InstallStrategy::Script {
+                    unix: Some(RUSTUP_SCRIPT.to_string()),
+                    windows: None,
+                },
+            )
+        },
+        ToolSpec {
+            homepage: Some("https://rustup.rs".to_string()),
+            ..spec(
+                "cargo",
+                "Cargo",
+                &["cargo"],
+                InstallStrategy::Script {
+                    unix: Some(RUSTUP_SCRIPT.to_string()),
+                    windows: None,
+                },
+            )
+        },
+        rustup_target("aarch64-apple-darwin"),
+        rustup_target("x86_64-apple-darwin"),
+        ToolSpec {
+            paths: vec![
+                "C:\\Program Files (x86)\\Microsoft Visual Studio\\2022\\BuildTools".to_string(),
+                "C:\\Program Files (x86)\\Microsoft Visual Studio\\2019\\BuildTools".to_string(),
+                "C:\\Program Files\\Microsoft Visual Studio\\2022\\Community".to_string(),
+                "C:\\Program Files\\Microsoft Visual Studio\\2019\\Community".to_string(),
+            ],
+            homepage: Some(
+                "https://visualstudio.microsoft.com/downloads/#build-tools-for-visual-studio-2022".to_string(),
+            ),
+            platforms: vec![Platform::Windows],
+            ..spec("visualStudio", "Visual Studio Build Tools", &[], InstallStrategy::Download)
+        },
+        ToolSpec {
+            homepage: Some("https://github.com/tw93/Pake".to_string()),
+            ..spec(
+                "pake",
+                "Pake CLI",
+                &["pake"],
+                InstallStrategy::GlobalPackage {
+                    package: "pake-cli".to_string(),
+                    managers: vec!["bun".to_string(), "npm".to_string(), "pnpm".to_string()],
+                },
+            )
+        },
+    ]
+}
+
+pub struct ToolRegistry {
+    specs: Vec<ToolSpec>,
+}
+
+impl ToolRegistry {
+    /// 内置工具加上设置中的自定义工具，自定义工具可以覆盖同 id 的内置工具
+    pub fn load() -> Self {
+        let mut specs = builtin_tools();
+
+        let custom_tools = SettingsManager::new()
+            .and_then(|manager| manager.load())
+            .map(|settings| settings.custom_tools)
+            .unwrap_or_default();
+
+        for mut tool in custom_tools {
+            tool.custom = true;
+            match specs.iter_mut().find(|s| s.id == tool.id) {
+                Some(existing) => *existing = tool,
+                None => specs.push(tool),
+            }
+        }
+
+        Self { specs }
+    }
+
+    pub fn specs(&self) -> &[ToolSpec] {
+        &self.specs
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ToolSpec> {
+        self.specs.iter().find(|s| s.id == id)
+    }
+}
+
+/// 从命令输出中提取版本号，未指定正则时取第一个形如 1.2.3 的片段
+pub fn extract_version(output: &str, pattern: Option<&str>) -> Option<String> {
+    let pattern = pattern.unwrap_or(r"(\d+(?:\.\d+)+)");
+    let re = regex::Regex::new(pattern).ok()?;
+    let caps = re.captures(output)?;
+    caps.get(1)
+        .or_else(|| caps.get(0))
+        .map(|m| m.as_str().to_string())
+}
+
+/// 按数字段比较版本号，缺失的段按 0 处理，例如 1.77 == 1.77.0；
+/// 数字段相同时带预发布后缀的版本较低，例如 3.0.0-beta.1 < 3.0.0
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    fn parts(v: &str) -> Option<(Vec<u64>, Option<String>)> {
+        let re = regex::Regex::new(r"(\d+(?:\.\d+)*)(?:-([0-9A-Za-z]+(?:\.[0-9A-Za-z]+)*))?").ok()?;
+        let caps = re.captures(v)?;
+        let numbers = caps[1].split('.').map(|p| p.parse().ok()).collect::<Option<Vec<u64>>>()?;
+        Some((numbers, caps.get(2).map(|m| m.as_str().to_string())))
+    }
+
+    let (a, a_pre) = parts(a)?;
+    let (b, b_pre) = parts(b)?;
+    let len = a.len().max(b.len());
+    for i in 0..len {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            other => return Some(other),
+        }
+    }
+    Some(match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(x), Some(y)) => compare_pre_release(&x, &y),
+    })
+}
+
+// 按 semver 的规则逐段比较预发布标识：数字按大小比较且低于字母，前面相同时段数少的较低
+fn compare_pre_release(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_numeric_parts() {
+        assert_eq!(compare_versions("1.77", "1.77.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("1.77.2", "1.77"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("18", "18.0.1"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.10.0", "1.9.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v20.11.1", "18.0.0"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v3.0.0", "3.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("unknown", "1.0.0"), None);
+    }
+
+    #[test]
+    fn pre_release_is_lower_than_release() {
+        assert_eq!(compare_versions("3.0.0-beta.1", "3.0.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("3.0.1-beta.1", "3.0.0"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("3.0.0-beta.2", "3.0.0-beta.10"), Some(Ordering::Less));
+        assert_eq!(compare_versions("3.0.0-alpha", "3.0.0-alpha.1"), Some(Ordering::Less));
+        assert_eq!(compare_versions("3.0.0-1", "3.0.0-rc"), Some(Ordering::Less));
+        assert_eq!(compare_versions("3.0.0-rc.1", "3.0.0-beta.5"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v3.0.0-rc.1", "3.0.0-rc.1"), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn extracts_version_from_noisy_output() {
+        assert_eq!(extract_version("v20.11.1\n", None).as_deref(), Some("20.11.1"));
+        assert_eq!(
+            extract_version("rustc 1.79.0-nightly (7f2fc33da 2024-04-22)", None).as_deref(),
+            Some("1.79.0")
+        );
+        assert_eq!(
+            extract_version("warning: something 2\ncargo 1.77.2 (e52e36006 2024-03-26)", None).as_deref(),
+            Some("1.77.2")
+        );
+        assert_eq!(extract_version("pnpm 9", None), None);
+        assert_eq!(extract_version("no version here", None), None);
+        assert_eq!(
+            extract_version("Python 3.12.1\npip 24.0 from /usr/lib", Some(r"pip (\d+\.\d+)")).as_deref(),
+            Some("24.0")
+        );
+        assert_eq!(
+            extract_version("wasm32-unknown-unknown\nx86_64-unknown-linux-gnu\n", Some("(?m)^(x86_64-unknown-linux-gnu)$")).as_deref(),
+            Some("x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(extract_version("1.2.3", Some("(")), None);
+    }
+}
@@ -151,13 +151,16 @@ function App() {
     url: '',
     name: '',
     icon: '',
+    iconSource: '',
     width: 1200,
     height: 780,
     useLocalFile: false,
+    localSource: '',
     fullscreen: false,
     hideTitleBar: false,
     multiArch: false,
     inject: [],
+    snippets: [],
     debug: false,
     activationShortcut: '',
     alwaysOnTop: false,
@@ -165,13 +168,28 @@ function App() {
     userAgent: '',
     showSystemTray: false,
     systemTrayIcon: '',
-    safeDomain: []
+    safeDomain: [],
+    transparent: false,
+    darkMode: false,
+    disabledWebShortcuts: false,
+    appVersion: '',
+    installerLanguage: '',
+    proxyUrl: '',
+    title: '',
+    hideOnClose: false,
+    incognito: false,
+    extraArgs: [],
+    pakeVersion: '',
+    env: [],
+    proxy: { http: '', https: '', noProxy: '' }
   });
   const [activeTab, setActiveTab] = useState(0);
   const [buildStatus, setBuildStatus] = useState(null);
   const [buildOutput, setBuildOutput] = useState('');
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState(null);
+  // 后端保存项目时返回的按字段分组的校验错误
+  const [fieldErrors, setFieldErrors] = useState({});
   const [snackbar, setSnackbar] = useState({
     open: false,
     message: '',
@@ -242,13 +260,16 @@ function App() {
         url: '',
         name: '',
         icon: '',
+        iconSource: '',
         width: 1200,
         height: 780,
         useLocalFile: false,
+        localSource: '',
         fullscreen: false,
         hideTitleBar: false,
         multiArch: false,
         inject: [],
+        snippets: [],
         debug: false,
         activationShortcut: '',
         alwaysOnTop: false,
@@ -256,7 +277,20 @@ function App() {
         userAgent: '',
         showSystemTray: false,
         systemTrayIcon: '',
-        safeDomain: []
+        safeDomain: [],
+        transparent: false,
+        darkMode: false,
+        disabledWebShortcuts: false,
+        appVersion: '',
+        installerLanguage: '',
+        proxyUrl: '',
+        title: '',
+        hideOnClose: false,
+        incognito: false,
+        extraArgs: [],
+        pakeVersion: '',
+        env: [],
+        proxy: { http: '', https: '', noProxy: '' }
       });
     } else {
       try {
@@ -270,13 +304,16 @@ function App() {
           url: loadedProject.config.url || '',
           name: loadedProject.config.name || '',
           icon: loadedProject.config.icon || '',
+          iconSource: loadedProject.config.iconSource || '',
           width: loadedProject.config.width || 1200,
           height: loadedProject.config.height || 780,
           useLocalFile: loadedProject.config.useLocalFile || false,
+          localSource: loadedProject.config.localSource || '',
           fullscreen: loadedProject.config.fullscreen || false,
           hideTitleBar: loadedProject.config.hideTitleBar || false,
           multiArch: loadedProject.config.multiArch || false,
           inject: loadedProject.config.inject || [],
+          snippets: loadedProject.config.snippets || [],
           debug: loadedProject.config.debug || false,
           activationShortcut: loadedProject.config.activationShortcut || '',
           alwaysOnTop: loadedProject.config.alwaysOnTop || false,
@@ -284,7 +321,20 @@ function App() {
           userAgent: loadedProject.config.userAgent || '',
           showSystemTray: loadedProject.config.showSystemTray || false,
           systemTrayIcon: loadedProject.config.systemTrayIcon || '',
-          safeDomain: loadedProject.config.safeDomain || []
+          safeDomain: loadedProject.config.safeDomain || [],
+          transparent: loadedProject.config.transparent || false,
+          darkMode: loadedProject.config.darkMode || false,
+          disabledWebShortcuts: loadedProject.config.disabledWebShortcuts || false,
+          appVersion: loadedProject.config.appVersion || '',
+          installerLanguage: loadedProject.config.installerLanguage || '',
+          proxyUrl: loadedProject.config.proxyUrl || '',
+          title: loadedProject.config.title || '',
+          hideOnClose: loadedProject.config.hideOnClose || false,
+          incognito: loadedProject.config.incognito || false,
+          extraArgs: loadedProject.config.extraArgs || [],
+          pakeVersion: loadedProject.config.pakeVersion || '',
+          env: loadedProject.config.env || [],
+          proxy: loadedProject.config.proxy || { http: '', https: '', noProxy: '' }
         });
         showSnackbar('项目加载成功', 'success');
       } catch (error) {
@@ -295,37 +345,32 @@ function App() {
   };
 
   const handleSaveProject = async () => {
-    if (!config.url?.trim()) {
-      showSnackbar('请输入 URL', 'error');
-      return;
-    }
-    
-    if (!config.name?.trim()) {
-      showSnackbar('请输入应用名称', 'error');
-      return;
-    }
-
+    // 名称、URL 和窗口尺寸由后端校验，错误按字段显示在表单上
     try {
       setLoading(true);
       const project = {
         id: currentProject?.id || Date.now().toString(),
-        name: config.name.trim(),
+        name: (config.name || '').trim(),
         config: {
           ...config,
-          url: config.url.trim(),
-          name: config.name.trim()
+          url: (config.url || '').trim(),
+          name: (config.name || '').trim()
         },
         lastModified: Date.now()
       };
       
-      await invoke('save_project', { project });
-      setCurrentProject(project);
+      // 本地应用保存时会复制到项目目录，url 会改为项目内的路径
+      const saved = await invoke('save_project', { project });
+      setFieldErrors({});
+      setCurrentProject(saved);
+      setConfig(prev => ({ ...prev, ...saved.config }));
       await loadProjects();
       showSnackbar('项目保存成功', 'success');
       setActiveTab(0); // 返回项目列表
     } catch (error) {
       console.error('Failed to save project:', error);
-      showSnackbar('保存项目失败：' + error.message, 'error');
+      setFieldErrors(error?.fields || {});
+      showSnackbar('保存项目失败：' + (error?.message || error), 'error');
     } finally {
       setLoading(false);
     }
@@ -340,15 +385,23 @@ function App() {
         lastModified: Date.now()
       };
       
-      await invoke('save_project', { project });
-      setCurrentProject(project);
+      let saved;
+      try {
+        saved = await invoke('save_project', { project });
+      } catch (saveError) {
+        setFieldErrors(saveError?.fields || {});
+        throw new Error(saveError?.message || saveError);
+      }
+      setFieldErrors({});
+      setCurrentProject(saved);
+      setConfig(prev => ({ ...prev, ...saved.config }));
       await loadProjects();
       
       setBuildStatus('building');
       setBuildOutput('Building application...\n');
       showSnackbar('开始构建应用...', 'info');
       
-      await invoke('build_pake_app', { config, projectId: project.id });
+      await invoke('build_pake_app', { config: saved.config, projectId: saved.id });
       setBuildStatus('success');
       setBuildOutput(prev => prev + '\nBuild completed successfully!');
       showSnackbar('构建完成', 'success');
@@ -589,6 +642,7 @@ function App() {
                 buildStatus={buildStatus}
                 buildOutput={buildOutput}
                 currentProject={currentProject}
+                fieldErrors={fieldErrors}
               />
             </TabPanel>
             
@@ -0,0 +1,90 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import {
+  Box,
+  Typography,
+  Button,
+  Paper,
+  Chip,
+  Stack
+} from '@mui/material';
+
+// 项目的构建历史，环境变量中的密钥由后端脱敏
+const BuildHistory = ({ projectId, refreshKey }) => {
+  const [records, setRecords] = useState([]);
+  const [expanded, setExpanded] = useState(null);
+
+  const loadHistory = async () => {
+    try {
+      setRecords(await invoke('get_build_history', { projectId }));
+    } catch (error) {
+      console.error('Failed to load build history:', error);
+    }
+  };
+
+  useEffect(() => {
+    if (projectId) {
+      loadHistory();
+    }
+  }, [projectId, refreshKey]);
+
+  const clearHistory = async () => {
+    try {
+      await invoke('clear_build_history', { projectId });
+      setRecords([]);
+    } catch (error) {
+      console.error('Failed to clear build history:', error);
+    }
+  };
+
+  if (!projectId) return null;
+
+  return (
+    <Paper sx={{ p: 2, mb: 3 }} variant="outlined">
+      <Box sx={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', mb: 1 }}>
+        <Typography variant="subtitle1">构建历史</Typography>
+        {records.length > 0 && (
+          <Button size="small" onClick={clearHistory}>清空</Button>
+        )}
+      </Box>
+      {records.length === 0 ? (
+        <Typography variant="body2" color="textSecondary">暂无构建记录</Typography>
+      ) : (
+        <Stack spacing={1}>
+          {records.map((record, index) => (
+            <Box key={record.startedAt}>
+              <Box
+                sx={{ display: 'flex', alignItems: 'center', gap: 1, cursor: 'pointer' }}
+                onClick={() => setExpanded(expanded === index ? null : index)}
+              >
+                <Chip
+                  size="small"
+                  label={record.success ? '成功' : '失败'}
+                  color={record.success ? 'success' : 'error'}
+                />
+                <Typography variant="body2">
+                  {new Date(record.startedAt).toLocaleString()}
+                  {' · '}
+                  {Math.round((record.finishedAt - record.startedAt) / 1000)}s
+                </Typography>
+              </Box>
+              {expanded === index && (
+                <Box sx={{ mt: 1, ml: 1, fontFamily: 'monospace', fontSize: '0.75rem', wordBreak: 'break-all' }}>
+                  <div>{record.command}</div>
+                  {record.env.map(v => (
+                    <div key={v.name}>{v.name}={v.value}</div>
+                  ))}
+                  {record.error && (
+                    <Typography variant="body2" color="error">{record.error}</Typography>
+                  )}
+                </Box>
+              )}
+            </Box>
+          ))}
+        </Stack>
+      )}
+    </Paper>
+  );
+};
+
+export default BuildHistory;
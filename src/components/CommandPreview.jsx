@@ -59,6 +59,21 @@ const CommandPreview = ({ config }) => {
     config.safeDomain?.forEach(domain => {
       parts.push(`--safe-domain "${domain}"`);
     });
+
+    if (config.transparent) parts.push('--transparent');
+    if (config.darkMode) parts.push('--dark-mode');
+    if (config.disabledWebShortcuts) parts.push('--disabled-web-shortcuts');
+    if (config.appVersion) parts.push(`--app-version ${config.appVersion}`);
+    if (config.installerLanguage) parts.push(`--installer-language ${config.installerLanguage}`);
+    if (config.proxyUrl) parts.push(`--proxy-url "${config.proxyUrl}"`);
+    if (config.title) parts.push(`--title "${config.title}"`);
+    if (config.hideOnClose) parts.push('--hide-on-close');
+    if (config.incognito) parts.push('--incognito');
+
+    // 额外参数原样追加，包含空格时加引号显示
+    config.extraArgs?.forEach(arg => {
+      parts.push(/\s/.test(arg) ? `"${arg}"` : arg);
+    });
     
     return parts.join(' ');
   };
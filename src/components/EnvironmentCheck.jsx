@@ -1,6 +1,8 @@
 import React, { useState, useEffect } from 'react';
 import { CheckCircle, XCircle, AlertCircle, Download, RefreshCw } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { save } from '@tauri-apps/plugin-dialog';
 
 const EnvironmentCheck = () => {
   const [environment, setEnvironment] = useState({
@@ -12,16 +14,160 @@ const EnvironmentCheck = () => {
   });
   
   const [isChecking, setIsChecking] = useState(false);
+  const [linuxDeps, setLinuxDeps] = useState(null);
+  const [toolNames, setToolNames] = useState({});
+  const [installing, setInstalling] = useState(null);
+  const [installLog, setInstallLog] = useState([]);
+  const [installResult, setInstallResult] = useState(null);
+  const [pakeOptions, setPakeOptions] = useState({
+    packageManager: '',
+    version: '',
+    managed: false
+  });
+  const [pakeVersions, setPakeVersions] = useState([]);
+  const [cacheVersion, setCacheVersion] = useState('');
+  const [cacheTarball, setCacheTarball] = useState('');
+  const [effectiveEnv, setEffectiveEnv] = useState(null);
+  const [envFilter, setEnvFilter] = useState('');
+  const [reportStatus, setReportStatus] = useState('');
+
+  // 监听安装输出事件
+  useEffect(() => {
+    const unlisten = listen('install-output', (event) => {
+      const { stream, line } = event.payload;
+      setInstallLog(prev => [...prev, { stream, line }]);
+    });
 
+    return () => {
+      unlisten.then(unlistenFn => unlistenFn());
+    };
+  }, []);
+
+  // 后台检测到 PATH 或工具变化（例如在终端中安装了工具）后推送新的结果
   useEffect(() => {
+    const unlisten = listen('environment-changed', (event) => {
+      setEnvironment(event.payload);
+    });
+
+    return () => {
+      unlisten.then(unlistenFn => unlistenFn());
+    };
+  }, []);
+
+  useEffect(() => {
+    loadToolNames();
+    loadPakeVersions();
     checkEnvironment();
   }, []);
 
-  const checkEnvironment = async () => {
+  const loadToolNames = async () => {
+    try {
+      const specs = await invoke('get_tool_specs');
+      setToolNames(Object.fromEntries(specs.map(spec => [spec.id, spec.name])));
+    } catch (error) {
+      console.error('Failed to load tool list:', error);
+    }
+  };
+
+  const loadPakeVersions = async () => {
+    try {
+      setPakeVersions(await invoke('list_pake_versions'));
+    } catch (error) {
+      console.error('Failed to load cached pake-cli versions:', error);
+    }
+  };
+
+  const installPakeVersion = async () => {
+    setInstalling('pake');
+    setInstallLog([]);
+    setInstallResult(null);
+    try {
+      const installed = await invoke('install_pake_version', {
+        version: cacheVersion.trim() || null,
+        source: { tarball: cacheTarball.trim() || null }
+      });
+      setInstallResult({ tool: 'pake', success: true, command: `pake-cli ${installed.version} cached at ${installed.path}` });
+      await loadPakeVersions();
+    } catch (error) {
+      console.error('Failed to cache pake-cli:', error);
+      setInstallResult({ tool: 'pake', success: false, error: String(error) });
+    } finally {
+      setInstalling(null);
+    }
+  };
+
+  const removePakeVersion = async (version) => {
+    try {
+      await invoke('remove_pake_version', { version });
+      await loadPakeVersions();
+    } catch (error) {
+      console.error(`Failed to remove pake-cli ${version}:`, error);
+    }
+  };
+
+  const toggleEffectiveEnv = async () => {
+    if (effectiveEnv) {
+      setEffectiveEnv(null);
+      return;
+    }
+    try {
+      setEffectiveEnv(await invoke('get_effective_environment'));
+    } catch (error) {
+      console.error('Failed to load effective environment:', error);
+    }
+  };
+
+  const copyDoctorReport = async () => {
+    setReportStatus('Generating report...');
+    try {
+      const report = await invoke('generate_doctor_report', { format: 'markdown' });
+      await navigator.clipboard.writeText(report);
+      setReportStatus('Report copied to clipboard');
+    } catch (error) {
+      setReportStatus(`Failed to generate report: ${error}`);
+    }
+  };
+
+  const saveDoctorReport = async () => {
+    try {
+      const savePath = await save({
+        defaultPath: 'pake-gui-doctor.md',
+        filters: [
+          { name: 'Markdown', extensions: ['md'] },
+          { name: 'JSON', extensions: ['json'] }
+        ]
+      });
+      if (!savePath) return;
+      setReportStatus('Generating report...');
+      const format = savePath.toLowerCase().endsWith('.json') ? 'json' : 'markdown';
+      await invoke('generate_doctor_report', { format, savePath });
+      setReportStatus(`Report saved to ${savePath}`);
+    } catch (error) {
+      setReportStatus(`Failed to save report: ${error}`);
+    }
+  };
+
+  const envSourceLabels = {
+    process: 'process',
+    loginShell: 'login shell',
+    searchPath: 'search path'
+  };
+
+  const getToolName = (tool) => {
+    if (tool === 'linuxDeps') return 'Linux System Dependencies';
+    return toolNames[tool] || tool;
+  };
+
+  const checkEnvironment = async (force = false) => {
     setIsChecking(true);
     try {
-      const result = await invoke('check_environment');
+      const result = await invoke('check_environment', { force });
       setEnvironment(result);
+      if (result.linuxDeps && result.linuxDeps.status !== 'ok') {
+        setLinuxDeps(await invoke('check_linux_dependencies'));
+      } else {
+        setLinuxDeps(null);
+      }
     } catch (error) {
       console.error('Environment check failed:', error);
     } finally {
@@ -29,12 +175,29 @@ const EnvironmentCheck = () => {
     }
   };
 
-  const installTool = async (tool) => {
+  const recheckTool = async (tool) => {
+    setEnvironment(prev => ({ ...prev, [tool]: { ...prev[tool], status: 'checking' } }));
     try {
-      await invoke('install_tool', { tool });
-      checkEnvironment();
+      const status = await invoke('check_tool', { tool });
+      setEnvironment(prev => ({ ...prev, [tool]: status }));
+    } catch (error) {
+      console.error(`Failed to check ${tool}:`, error);
+    }
+  };
+
+  const installTool = async (tool, options = null) => {
+    setInstalling(tool);
+    setInstallLog([]);
+    setInstallResult(null);
+    try {
+      const result = await invoke('install_tool', { tool, options });
+      setInstallResult(result);
+      setEnvironment(prev => ({ ...prev, [tool]: result.status }));
     } catch (error) {
       console.error(`Failed to install ${tool}:`, error);
+      setInstallResult({ tool, success: false, error: String(error) });
+    } finally {
+      setInstalling(null);
     }
   };
 
@@ -54,8 +217,8 @@ const EnvironmentCheck = () => {
   const getStatusText = (tool, info) => {
     if (info.status === 'checking') return 'Checking...';
     if (info.status === 'ok') return `${tool} ${info.version} (Found)`;
-    if (info.status === 'warning') return `${tool} found but outdated (${info.version})`;
-    return `${tool} not found`;
+    if (info.status === 'warning') return info.diagnosis || `${tool} found but outdated (${info.version})`;
+    return info.diagnosis || `${tool} not found`;
   };
 
   return (
@@ -63,7 +226,7 @@ const EnvironmentCheck = () => {
       <div className="flex justify-between items-center mb-6">
         <h2 className="text-xl font-semibold">Environment Check</h2>
         <button
-          onClick={checkEnvironment}
+          onClick={() => checkEnvironment(true)}
           disabled={isChecking}
           className="flex items-center space-x-2 bg-blue-600 text-white px-4 py-2 rounded-md hover:bg-blue-700 disabled:opacity-50"
         >
@@ -78,27 +241,242 @@ const EnvironmentCheck = () => {
             <div className="flex items-center space-x-3">
               {getStatusIcon(info.status)}
               <div>
-                <h3 className="font-medium capitalize">{tool === 'bunjs' ? 'Bun.js' : tool === 'nodejs' ? 'Node.js' : tool === 'visualStudio' ? 'Visual Studio Build Tools' : tool}</h3>
-                <p className="text-sm text-gray-500">{getStatusText(tool, info)}</p>
+                <h3 className="font-medium">{getToolName(tool)}</h3>
+                <p className="text-sm text-gray-500">{getStatusText(getToolName(tool), info)}</p>
                 {info.path && (
                   <p className="text-xs text-gray-400">Path: {info.path}</p>
                 )}
+                {info.checkedAt && (
+                  <p className="text-xs text-gray-400">Checked: {new Date(info.checkedAt).toLocaleString()}</p>
+                )}
+                {info.status !== 'ok' && info.command && (
+                  <p className="text-xs text-gray-400">Command: <code>{info.command}</code></p>
+                )}
+                {info.status !== 'ok' && info.stderr && (
+                  <pre className="text-xs text-red-400 whitespace-pre-wrap break-all mt-1">{info.stderr}</pre>
+                )}
+                {info.status !== 'ok' && info.suggestedFix && (
+                  <p className="text-xs text-blue-600 mt-1">Suggested fix: {info.suggestedFix}</p>
+                )}
+                {info.installations?.map(installation => (
+                  <p key={installation.kind} className="text-xs text-gray-400">
+                    {installation.kind === 'managed' ? 'Managed' : 'Global'}: {installation.status === 'ok' ? `${installation.version} (${installation.path})` : 'not available'}
+                  </p>
+                ))}
               </div>
             </div>
             
-            {info.status === 'error' && (
+            <div className="flex items-center space-x-2">
               <button
-                onClick={() => installTool(tool)}
-                className="flex items-center space-x-2 bg-green-600 text-white px-3 py-1 rounded-md hover:bg-green-700 text-sm"
+                onClick={() => recheckTool(tool)}
+                disabled={info.status === 'checking'}
+                className="flex items-center space-x-2 border px-3 py-1 rounded-md hover:bg-gray-50 text-sm disabled:opacity-50"
               >
-                <Download size={14} />
-                <span>Install</span>
+                <RefreshCw size={14} />
+                <span>Recheck</span>
               </button>
-            )}
+              {info.status === 'error' && (
+                <button
+                  onClick={() => installTool(tool)}
+                  disabled={installing !== null}
+                  className="flex items-center space-x-2 bg-green-600 text-white px-3 py-1 rounded-md hover:bg-green-700 text-sm"
+                >
+                  <Download size={14} />
+                  <span>{installing === tool ? 'Installing...' : 'Install'}</span>
+                </button>
+              )}
+            </div>
           </div>
         ))}
       </div>
 
+      <div className="mt-6 p-4 border rounded-lg">
+        <h3 className="font-medium mb-2">Pake CLI Installation</h3>
+        <div className="flex flex-wrap items-center gap-3 text-sm">
+          <select
+            value={pakeOptions.packageManager}
+            onChange={(e) => setPakeOptions(prev => ({ ...prev, packageManager: e.target.value }))}
+            className="border rounded-md px-2 py-1"
+          >
+            <option value="">Auto (bun → npm → pnpm)</option>
+            <option value="bun">bun</option>
+            <option value="npm">npm</option>
+            <option value="pnpm">pnpm</option>
+          </select>
+          <input
+            value={pakeOptions.version}
+            onChange={(e) => setPakeOptions(prev => ({ ...prev, version: e.target.value }))}
+            placeholder="latest"
+            className="border rounded-md px-2 py-1 w-32"
+          />
+          <label className="flex items-center space-x-1">
+            <input
+              type="checkbox"
+              checked={pakeOptions.managed}
+              onChange={(e) => setPakeOptions(prev => ({ ...prev, managed: e.target.checked }))}
+            />
+            <span>Install into GUI-managed directory</span>
+          </label>
+          <button
+            onClick={() => installTool('pake', {
+              packageManager: pakeOptions.packageManager || null,
+              version: pakeOptions.version.trim() || null,
+              managed: pakeOptions.managed
+            })}
+            disabled={installing !== null}
+            className="flex items-center space-x-2 bg-green-600 text-white px-3 py-1 rounded-md hover:bg-green-700 disabled:opacity-50"
+          >
+            <Download size={14} />
+            <span>{installing === 'pake' ? 'Installing...' : 'Install pake-cli'}</span>
+          </button>
+        </div>
+      </div>
+
+      <div className="mt-6 p-4 border rounded-lg">
+        <h3 className="font-medium mb-2">Cached pake-cli Versions</h3>
+        <p className="text-sm text-gray-600 mb-2">
+          Projects with a pinned pake-cli version are built with the matching cached version.
+        </p>
+        {pakeVersions.length === 0 ? (
+          <p className="text-sm text-gray-500 mb-2">No cached versions</p>
+        ) : (
+          <ul className="text-sm mb-2 space-y-1">
+            {pakeVersions.map(item => (
+              <li key={item.version} className="flex items-center justify-between">
+                <span><strong>{item.version}</strong> <span className="text-gray-500 break-all">{item.path}</span></span>
+                <button
+                  onClick={() => removePakeVersion(item.version)}
+                  className="text-red-600 hover:underline"
+                >
+                  Remove
+                </button>
+              </li>
+            ))}
+          </ul>
+        )}
+        <div className="flex flex-wrap items-center gap-3 text-sm">
+          <input
+            value={cacheVersion}
+            onChange={(e) => setCacheVersion(e.target.value)}
+            placeholder="version, e.g. 3.1.1"
+            className="border rounded-md px-2 py-1 w-40"
+          />
+          <input
+            value={cacheTarball}
+            onChange={(e) => setCacheTarball(e.target.value)}
+            placeholder="or local tarball path (.tgz)"
+            className="border rounded-md px-2 py-1 flex-1"
+          />
+          <button
+            onClick={installPakeVersion}
+            disabled={installing !== null}
+            className="flex items-center space-x-2 bg-green-600 text-white px-3 py-1 rounded-md hover:bg-green-700 disabled:opacity-50"
+          >
+            <Download size={14} />
+            <span>Add to cache</span>
+          </button>
+        </div>
+      </div>
+
+      <div className="mt-6 p-4 border rounded-lg">
+        <h3 className="font-medium mb-2">Doctor Report</h3>
+        <p className="text-sm text-gray-600 mb-2">
+          Versions, PATH, system dependencies and disk space in one report to attach to build issues.
+        </p>
+        <div className="flex items-center gap-3 text-sm">
+          <button
+            onClick={copyDoctorReport}
+            className="bg-blue-600 text-white px-3 py-1 rounded-md hover:bg-blue-700"
+          >
+            Copy as Markdown
+          </button>
+          <button
+            onClick={saveDoctorReport}
+            className="border px-3 py-1 rounded-md hover:bg-gray-50"
+          >
+            Save to file
+          </button>
+          {reportStatus && <span className="text-gray-600">{reportStatus}</span>}
+        </div>
+      </div>
+
+      <div className="mt-6 p-4 border rounded-lg">
+        <div className="flex justify-between items-center">
+          <h3 className="font-medium">Effective Environment</h3>
+          <button onClick={toggleEffectiveEnv} className="text-sm text-blue-600 hover:underline">
+            {effectiveEnv ? 'Hide' : 'Show'}
+          </button>
+        </div>
+        {effectiveEnv && (
+          <div className="mt-2 text-sm">
+            <p className="text-gray-600 mb-2">
+              {effectiveEnv.error
+                ? `Login shell import failed (${effectiveEnv.shell}): ${effectiveEnv.error}`
+                : effectiveEnv.shell
+                  ? `Imported from login shell ${effectiveEnv.shell}`
+                  : 'Using the process environment'}
+            </p>
+            <input
+              value={envFilter}
+              onChange={(e) => setEnvFilter(e.target.value)}
+              placeholder="Filter variables"
+              className="border rounded-md px-2 py-1 w-full mb-2"
+            />
+            <div className="max-h-64 overflow-y-auto font-mono text-xs bg-gray-50 rounded p-2">
+              {effectiveEnv.vars
+                .filter(v => v.name.toLowerCase().includes(envFilter.toLowerCase()))
+                .map(v => (
+                  <div key={v.name} className="break-all">
+                    <span className="text-gray-400">[{envSourceLabels[v.source] || v.source}]</span>{' '}
+                    <strong>{v.name}</strong>={v.value}
+                  </div>
+                ))}
+            </div>
+          </div>
+        )}
+      </div>
+
+      {(installing || installResult) && (
+        <div className="mt-6 p-4 border rounded-lg">
+          <h3 className="font-medium mb-2">
+            {installing
+              ? `Installing ${getToolName(installing)}...`
+              : installResult.success
+                ? `${getToolName(installResult.tool)}: ${installResult.openedUrl ? `opened ${installResult.openedUrl}` : 'install finished'}`
+                : `${getToolName(installResult.tool)}: install failed${installResult.exitCode != null ? ` (exit code ${installResult.exitCode})` : ''}`}
+          </h3>
+          {installResult?.error && (
+            <p className="text-sm text-red-600 mb-2">{installResult.error}</p>
+          )}
+          {installResult?.command && (
+            <p className="text-xs text-gray-400 mb-2">Command: <code>{installResult.command}</code></p>
+          )}
+          {installLog.length > 0 && (
+            <pre className="bg-gray-900 text-gray-100 text-xs p-3 rounded max-h-64 overflow-auto whitespace-pre-wrap">
+              {installLog.map((entry, index) => (
+                <div key={index} className={entry.stream === 'stderr' ? 'text-yellow-300' : ''}>{entry.line}</div>
+              ))}
+            </pre>
+          )}
+        </div>
+      )}
+
+      {linuxDeps && linuxDeps.missingPackages.length > 0 && (
+        <div className="mt-6 p-4 bg-yellow-50 rounded-lg">
+          <h3 className="font-medium text-yellow-900 mb-2">Missing Linux Dependencies</h3>
+          <div className="text-sm text-yellow-800 space-y-1">
+            {linuxDeps.dependencies.filter(dep => !dep.installed).map(dep => (
+              <p key={dep.name}>• <strong>{dep.name}</strong></p>
+            ))}
+            {linuxDeps.installCommand ? (
+              <p className="font-mono text-xs mt-2 break-all">{linuxDeps.installCommand}</p>
+            ) : (
+              <p className="mt-2">Install with your package manager: {linuxDeps.missingPackages.join(', ')}</p>
+            )}
+          </div>
+        </div>
+      )}
+
       <div className="mt-6 p-4 bg-blue-50 rounded-lg">
         <h3 className="font-medium text-blue-900 mb-2">Installation Guide</h3>
         <div className="text-sm text-blue-800 space-y-1">
@@ -0,0 +1,175 @@
+import React, { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import {
+  Box,
+  Typography,
+  Button,
+  Stack,
+  Chip,
+  TextField,
+  MenuItem,
+  Dialog,
+  DialogTitle,
+  DialogContent,
+  DialogActions,
+  Alert
+} from '@mui/material';
+import {
+  Add as AddIcon,
+  FolderOpen as FolderOpenIcon
+} from '@mui/icons-material';
+
+const MANAGED_PREFIX = 'inject/';
+
+// 项目的注入文件：复制到项目 inject 目录中，也可以直接编写 CSS/JS
+const InjectManager = ({ projectId, inject = [], onChange, error }) => {
+  const [editor, setEditor] = useState(null);
+  const [editorError, setEditorError] = useState('');
+
+  const fileName = (path) => path.slice(MANAGED_PREFIX.length);
+
+  const addFile = async () => {
+    try {
+      const selected = await open({
+        multiple: false,
+        filters: [{ name: 'CSS / JS', extensions: ['css', 'js'] }]
+      });
+      if (!selected) return;
+      // 新项目还没有目录，先记录原始路径，保存项目时再复制
+      const path = projectId
+        ? (await invoke('import_inject_file', { projectId, path: selected })).path
+        : selected;
+      if (!inject.includes(path)) {
+        onChange([...inject, path]);
+      }
+    } catch (error) {
+      console.error('Failed to add inject file:', error);
+      alert('添加注入文件失败：' + error);
+    }
+  };
+
+  const openEditor = async (path) => {
+    setEditorError('');
+    if (!path) {
+      setEditor({ name: '', type: 'css', content: '', isNew: true });
+      return;
+    }
+    try {
+      const name = fileName(path);
+      const content = await invoke('read_inject_file', { projectId, name });
+      setEditor({ name, content, isNew: false });
+    } catch (error) {
+      console.error('Failed to read inject file:', error);
+    }
+  };
+
+  const saveEditor = async () => {
+    const name = editor.isNew && !/\.(css|js)$/i.test(editor.name)
+      ? `${editor.name}.${editor.type}`
+      : editor.name;
+    try {
+      const file = await invoke('save_inject_file', { projectId, name, content: editor.content });
+      if (!inject.includes(file.path)) {
+        onChange([...inject, file.path]);
+      }
+      setEditor(null);
+    } catch (error) {
+      setEditorError(String(error));
+    }
+  };
+
+  const removeFile = async (path) => {
+    onChange(inject.filter(item => item !== path));
+    // 项目内的文件一起删除，项目外的文件只移除引用
+    if (projectId && path.startsWith(MANAGED_PREFIX)) {
+      try {
+        await invoke('delete_inject_file', { projectId, name: fileName(path) });
+      } catch (error) {
+        console.error('Failed to delete inject file:', error);
+      }
+    }
+  };
+
+  return (
+    <Box sx={{ mb: 2 }}>
+      <Typography variant="subtitle2" gutterBottom>
+        注入文件
+      </Typography>
+      <Stack spacing={1}>
+        {inject.map(path => (
+          <Chip
+            key={path}
+            label={path}
+            color="primary"
+            variant="outlined"
+            onClick={projectId && path.startsWith(MANAGED_PREFIX) ? () => openEditor(path) : undefined}
+            onDelete={() => removeFile(path)}
+          />
+        ))}
+        {error && <Typography variant="caption" color="error">{error}</Typography>}
+        <Box sx={{ display: 'flex', gap: 1 }}>
+          <Button
+            variant="outlined"
+            onClick={() => openEditor(null)}
+            disabled={!projectId}
+            startIcon={<AddIcon />}
+          >
+            新建
+          </Button>
+          <Button variant="outlined" onClick={addFile} startIcon={<FolderOpenIcon />}>
+            添加文件
+          </Button>
+        </Box>
+        <Typography variant="caption" color="textSecondary">
+          {projectId ? '文件保存在项目的 inject 目录中，点击可编辑' : '保存项目后可以新建和编辑注入文件'}
+        </Typography>
+      </Stack>
+
+      <Dialog open={!!editor} onClose={() => setEditor(null)} fullWidth maxWidth="md">
+        <DialogTitle>{editor?.isNew ? '新建注入文件' : `编辑 ${editor?.name}`}</DialogTitle>
+        <DialogContent>
+          {editor?.isNew && (
+            <Box sx={{ display: 'flex', gap: 2, mt: 1 }}>
+              <TextField
+                fullWidth
+                label="文件名"
+                value={editor.name}
+                onChange={(e) => setEditor({ ...editor, name: e.target.value })}
+                placeholder="custom"
+              />
+              <TextField
+                select
+                label="类型"
+                value={editor.type}
+                onChange={(e) => setEditor({ ...editor, type: e.target.value })}
+                sx={{ minWidth: 120 }}
+              >
+                <MenuItem value="css">CSS</MenuItem>
+                <MenuItem value="js">JS</MenuItem>
+              </TextField>
+            </Box>
+          )}
+          <TextField
+            fullWidth
+            multiline
+            minRows={12}
+            margin="normal"
+            value={editor?.content || ''}
+            onChange={(e) => setEditor({ ...editor, content: e.target.value })}
+            InputProps={{ sx: { fontFamily: 'monospace', fontSize: 13 } }}
+          />
+          {editorError && <Alert severity="error">{editorError}</Alert>}
+        </DialogContent>
+        <DialogActions>
+          <Button onClick={() => setEditor(null)}>取消</Button>
+          <Button variant="contained" onClick={saveEditor} disabled={!editor?.name?.trim()}>
+            保存
+          </Button>
+        </DialogActions>
+      </Dialog>
+    </Box>
+  );
+};
+
+export default InjectManager;
@@ -16,6 +16,20 @@ import {
   Chip
 } from '@mui/material';
 import { open } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
+import SnippetLibrary from './SnippetLibrary';
+
+const emptyCustomTool = {
+  id: '',
+  name: '',
+  binary: '',
+  versionArgs: '--version',
+  minVersion: '',
+  homepage: ''
+};
+
+// 可以单独指定路径的可执行文件
+const overridableBinaries = ['pake', 'node', 'bun', 'rustc', 'cargo'];
 
 const Settings = ({ onSettingsChange }) => {
   const [settings, setSettings] = useState({
@@ -33,6 +47,94 @@ const Settings = ({ onSettingsChange }) => {
     timestamp: true
   });
 
+  const [customTools, setCustomTools] = useState([]);
+  const [newTool, setNewTool] = useState(emptyCustomTool);
+  const [toolError, setToolError] = useState('');
+
+  const [pathSettings, setPathSettings] = useState({ binaryPaths: {}, extraPath: [], discovered: [] });
+  const [extraPathText, setExtraPathText] = useState('');
+  const [pathMessage, setPathMessage] = useState({ text: '', error: false });
+
+  // 加载工具路径设置
+  const loadPathSettings = async () => {
+    try {
+      const result = await invoke('get_path_settings');
+      setPathSettings(result);
+      setExtraPathText(result.extraPath.join('\n'));
+    } catch (error) {
+      console.error('Failed to load path settings:', error);
+    }
+  };
+
+  const savePathSettings = async () => {
+    try {
+      await invoke('save_path_settings', {
+        binaryPaths: pathSettings.binaryPaths,
+        extraPath: extraPathText.split('\n')
+      });
+      setPathMessage({ text: '已保存，重新检查环境后生效', error: false });
+      loadPathSettings();
+    } catch (error) {
+      setPathMessage({ text: String(error), error: true });
+    }
+  };
+
+  const selectBinaryPath = async (binary) => {
+    try {
+      const selected = await open({ multiple: false });
+      if (selected) {
+        setPathSettings(prev => ({ ...prev, binaryPaths: { ...prev.binaryPaths, [binary]: selected } }));
+      }
+    } catch (error) {
+      console.error('Failed to open file dialog:', error);
+    }
+  };
+
+  // 加载自定义工具
+  const loadCustomTools = async () => {
+    try {
+      const specs = await invoke('get_tool_specs');
+      setCustomTools(specs.filter(spec => spec.custom));
+    } catch (error) {
+      console.error('Failed to load custom tools:', error);
+    }
+  };
+
+  useEffect(() => {
+    loadCustomTools();
+    loadPathSettings();
+  }, []);
+
+  const addCustomTool = async () => {
+    try {
+      await invoke('save_custom_tool', {
+        tool: {
+          id: newTool.id.trim(),
+          name: newTool.name.trim(),
+          binaries: [newTool.binary.trim()],
+          versionArgs: newTool.versionArgs.trim() ? newTool.versionArgs.trim().split(/\s+/) : [],
+          minVersion: newTool.minVersion.trim() || null,
+          homepage: newTool.homepage.trim() || null,
+          install: { type: newTool.homepage.trim() ? 'download' : 'none' }
+        }
+      });
+      setNewTool(emptyCustomTool);
+      setToolError('');
+      loadCustomTools();
+    } catch (error) {
+      setToolError(String(error));
+    }
+  };
+
+  const removeCustomTool = async (toolId) => {
+    try {
+      await invoke('remove_custom_tool', { toolId });
+      loadCustomTools();
+    } catch (error) {
+      console.error('Failed to remove custom tool:', error);
+    }
+  };
+
   // 加载设置
   useEffect(() => {
     const loadSettings = async () => {
@@ -301,6 +403,144 @@ const Settings = ({ onSettingsChange }) => {
             </Box>
           </Paper>
         </Grid>
+
+        {/* 工具路径 */}
+        <Grid item xs={12} sx={{ width: '100%' }}>
+          <Paper sx={{ p: 3 }} variant="outlined">
+            <Typography variant="h6" gutterBottom>
+              工具路径
+            </Typography>
+
+            <Typography variant="body2" color="textSecondary" sx={{ mb: 2 }}>
+              从桌面启动时 PATH 中可能缺少 ~/.bun/bin、~/.cargo/bin 等目录，可以在这里指定可执行文件路径或额外的 PATH 目录，环境检查和构建都会使用
+            </Typography>
+
+            <Grid container spacing={2}>
+              {overridableBinaries.map(binary => (
+                <Grid item xs={12} sm={6} key={binary}>
+                  <TextField
+                    fullWidth
+                    size="small"
+                    label={binary}
+                    placeholder="自动查找"
+                    value={pathSettings.binaryPaths[binary] || ''}
+                    onChange={(e) => setPathSettings(prev => ({
+                      ...prev,
+                      binaryPaths: { ...prev.binaryPaths, [binary]: e.target.value }
+                    }))}
+                    InputProps={{
+                      endAdornment: (
+                        <Button onClick={() => selectBinaryPath(binary)} size="small">
+                          选择
+                        </Button>
+                      ),
+                    }}
+                  />
+                </Grid>
+              ))}
+            </Grid>
+
+            <TextField
+              fullWidth
+              multiline
+              minRows={2}
+              label="额外的 PATH 目录"
+              value={extraPathText}
+              onChange={(e) => setExtraPathText(e.target.value)}
+              placeholder="每行一个目录"
+              margin="normal"
+            />
+
+            {pathSettings.discovered.length > 0 && (
+              <Box sx={{ mb: 2 }}>
+                <Typography variant="subtitle2" gutterBottom>
+                  自动发现的目录:
+                </Typography>
+                <Box sx={{ display: 'flex', flexWrap: 'wrap', gap: 1 }}>
+                  {pathSettings.discovered.map(dir => (
+                    <Chip key={dir} label={dir} size="small" variant="outlined" />
+                  ))}
+                </Box>
+              </Box>
+            )}
+
+            {pathMessage.text && (
+              <Typography variant="body2" color={pathMessage.error ? 'error' : 'textSecondary'} sx={{ mb: 1 }}>
+                {pathMessage.text}
+              </Typography>
+            )}
+
+            <Button variant="contained" onClick={savePathSettings}>
+              保存路径设置
+            </Button>
+          </Paper>
+        </Grid>
+
+        {/* 代码片段库 */}
+        <Grid item xs={12} sx={{ width: '100%' }}>
+          <SnippetLibrary />
+        </Grid>
+
+        {/* 自定义工具 */}
+        <Grid item xs={12} sx={{ width: '100%' }}>
+          <Paper sx={{ p: 3 }} variant="outlined">
+            <Typography variant="h6" gutterBottom>
+              自定义工具
+            </Typography>
+
+            <Typography variant="body2" color="textSecondary" sx={{ mb: 2 }}>
+              添加需要在环境检查中一并检测的工具
+            </Typography>
+
+            <Box sx={{ display: 'flex', flexWrap: 'wrap', gap: 1, mb: 2 }}>
+              {customTools.map(tool => (
+                <Chip
+                  key={tool.id}
+                  label={`${tool.name} (${tool.binaries.join(', ')})`}
+                  onDelete={() => removeCustomTool(tool.id)}
+                  variant="outlined"
+                />
+              ))}
+            </Box>
+
+            <Grid container spacing={2}>
+              {[
+                { key: 'id', label: 'ID', placeholder: 'wasm-pack' },
+                { key: 'name', label: '名称', placeholder: 'wasm-pack' },
+                { key: 'binary', label: '可执行文件', placeholder: 'wasm-pack' },
+                { key: 'versionArgs', label: '版本参数', placeholder: '--version' },
+                { key: 'minVersion', label: '最低版本', placeholder: '0.12.0' },
+                { key: 'homepage', label: '下载页面', placeholder: 'https://example.com' }
+              ].map(({ key, label, placeholder }) => (
+                <Grid item xs={12} sm={6} md={4} key={key}>
+                  <TextField
+                    fullWidth
+                    size="small"
+                    label={label}
+                    placeholder={placeholder}
+                    value={newTool[key]}
+                    onChange={(e) => setNewTool(prev => ({ ...prev, [key]: e.target.value }))}
+                  />
+                </Grid>
+              ))}
+            </Grid>
+
+            {toolError && (
+              <Typography variant="body2" color="error" sx={{ mt: 1 }}>
+                {toolError}
+              </Typography>
+            )}
+
+            <Button
+              variant="contained"
+              sx={{ mt: 2 }}
+              onClick={addCustomTool}
+              disabled={!newTool.id.trim() || !newTool.name.trim() || !newTool.binary.trim()}
+            >
+              添加工具
+            </Button>
+          </Paper>
+        </Grid>
       </Grid>
     </Box>
   );
@@ -0,0 +1,166 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import {
+  Box,
+  Typography,
+  Button,
+  Paper,
+  Chip,
+  Stack,
+  TextField,
+  MenuItem,
+  Dialog,
+  DialogTitle,
+  DialogContent,
+  DialogActions,
+  Alert
+} from '@mui/material';
+import { Add as AddIcon } from '@mui/icons-material';
+
+const emptySnippet = { id: null, name: '', description: '', kind: 'css', content: '' };
+
+// 全局的 CSS/JS 片段库，项目按 id 引用，修改后引用它的项目在下次构建时使用新版本
+const SnippetLibrary = () => {
+  const [snippets, setSnippets] = useState([]);
+  const [editor, setEditor] = useState(null);
+  const [usage, setUsage] = useState({});
+  const [message, setMessage] = useState({ text: '', error: false });
+
+  const loadSnippets = async () => {
+    try {
+      setSnippets(await invoke('list_snippets'));
+    } catch (error) {
+      console.error('Failed to load snippets:', error);
+    }
+  };
+
+  useEffect(() => {
+    loadSnippets();
+  }, []);
+
+  const saveSnippet = async () => {
+    try {
+      const saved = await invoke('save_snippet', { snippet: editor });
+      setEditor(null);
+      setMessage({ text: `已保存 ${saved.name}（v${saved.version}）`, error: false });
+      await loadSnippets();
+    } catch (error) {
+      setMessage({ text: String(error), error: true });
+    }
+  };
+
+  const deleteSnippet = async (id) => {
+    try {
+      await invoke('delete_snippet', { id });
+      setMessage({ text: '', error: false });
+      await loadSnippets();
+    } catch (error) {
+      setMessage({ text: String(error), error: true });
+    }
+  };
+
+  const showUsage = async (id) => {
+    try {
+      const projects = await invoke('get_snippet_usage', { id });
+      setUsage(prev => ({ ...prev, [id]: projects }));
+    } catch (error) {
+      console.error('Failed to load snippet usage:', error);
+    }
+  };
+
+  return (
+    <Paper sx={{ p: 3 }} variant="outlined">
+      <Box sx={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', mb: 1 }}>
+        <Typography variant="h6">代码片段库</Typography>
+        <Button startIcon={<AddIcon />} onClick={() => setEditor(emptySnippet)}>
+          新建片段
+        </Button>
+      </Box>
+
+      <Typography variant="body2" color="textSecondary" sx={{ mb: 2 }}>
+        多个项目共用的 CSS/JS 片段，项目引用的片段在每次构建时使用最新版本
+      </Typography>
+
+      <Stack spacing={1}>
+        {snippets.map(snippet => (
+          <Box key={snippet.id} sx={{ display: 'flex', alignItems: 'center', gap: 1, flexWrap: 'wrap' }}>
+            <Chip label={snippet.kind.toUpperCase()} size="small" />
+            <Typography variant="body2" sx={{ fontWeight: 500 }}>{snippet.name}</Typography>
+            <Typography variant="caption" color="textSecondary">
+              {snippet.id} · v{snippet.version}{snippet.description && ` · ${snippet.description}`}
+            </Typography>
+            <Box sx={{ flexGrow: 1 }} />
+            {usage[snippet.id] && (
+              <Typography variant="caption" color="textSecondary">
+                {usage[snippet.id].length === 0
+                  ? '未被项目使用'
+                  : `使用中：${usage[snippet.id].map(u => u.projectName).join('、')}`}
+              </Typography>
+            )}
+            <Button size="small" onClick={() => showUsage(snippet.id)}>使用情况</Button>
+            <Button size="small" onClick={() => setEditor({ ...snippet })}>编辑</Button>
+            <Button size="small" color="error" onClick={() => deleteSnippet(snippet.id)}>删除</Button>
+          </Box>
+        ))}
+      </Stack>
+
+      {message.text && (
+        <Typography variant="body2" color={message.error ? 'error' : 'textSecondary'} sx={{ mt: 1 }}>
+          {message.text}
+        </Typography>
+      )}
+
+      <Dialog open={!!editor} onClose={() => setEditor(null)} fullWidth maxWidth="md">
+        <DialogTitle>{editor?.id ? `编辑 ${editor.name}` : '新建片段'}</DialogTitle>
+        <DialogContent>
+          <Box sx={{ display: 'flex', gap: 2, mt: 1 }}>
+            <TextField
+              fullWidth
+              label="名称"
+              value={editor?.name || ''}
+              onChange={(e) => setEditor({ ...editor, name: e.target.value })}
+            />
+            <TextField
+              select
+              label="类型"
+              value={editor?.kind || 'css'}
+              onChange={(e) => setEditor({ ...editor, kind: e.target.value })}
+              sx={{ minWidth: 120 }}
+            >
+              <MenuItem value="css">CSS</MenuItem>
+              <MenuItem value="js">JS</MenuItem>
+            </TextField>
+          </Box>
+          <TextField
+            fullWidth
+            label="说明"
+            margin="normal"
+            value={editor?.description || ''}
+            onChange={(e) => setEditor({ ...editor, description: e.target.value })}
+          />
+          <TextField
+            fullWidth
+            multiline
+            minRows={12}
+            value={editor?.content || ''}
+            onChange={(e) => setEditor({ ...editor, content: e.target.value })}
+            InputProps={{ sx: { fontFamily: 'monospace', fontSize: 13 } }}
+          />
+          {editor?.id && (
+            <Alert severity="info" sx={{ mt: 2 }}>
+              修改内容后版本号会增加，引用这个片段的项目在下次构建时使用新版本
+            </Alert>
+          )}
+        </DialogContent>
+        <DialogActions>
+          <Button onClick={() => setEditor(null)}>取消</Button>
+          <Button variant="contained" onClick={saveSnippet} disabled={!editor?.name?.trim()}>
+            保存
+          </Button>
+        </DialogActions>
+      </Dialog>
+    </Paper>
+  );
+};
+
+export default SnippetLibrary;